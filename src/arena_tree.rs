@@ -0,0 +1,287 @@
+use crate::binary_tree::{RefTreeNode, TraversalType, TreeNode};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+// #region ArenaTree
+/// A binary tree whose nodes are indices into vectors, which avoids the `Rc<RefCell<_>>`
+/// borrows of [`TreeNode`]. Convert a LeetCode tree with [`ArenaTree::from_tree`], work on the
+/// indices, and convert back with [`ArenaTree::to_tree`].
+///
+/// # Example
+/// ```
+/// use cauly_rust_leetcode_utils::arena_tree::*;
+/// use cauly_rust_leetcode_utils::binary_tree::*;
+/// let tree = TreeNode::from_string("[1,2,3,null,4]").unwrap();
+/// let mut arena = ArenaTree::from_tree(&tree);
+/// assert_eq!(vec![1, 2, 3, 4], arena.values);
+/// assert_eq!(Some(1), arena.parent[3]);
+/// let five = arena.add_node(5);
+/// arena.set_right(2, Some(five));
+/// assert_eq!("[1,2,3,null,4,null,5]", arena.to_tree().to_leetcode_string());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ArenaTree {
+    pub root: Option<usize>,
+    pub values: Vec<i32>,
+    pub left: Vec<Option<usize>>,
+    pub right: Vec<Option<usize>>,
+    pub parent: Vec<Option<usize>>,
+}
+
+impl ArenaTree {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// The number of nodes, including the ones no longer reachable from the root.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Add a node without parent or children, and return its index. The first node becomes the
+    /// root.
+    pub fn add_node(&mut self, val: i32) -> usize {
+        self.values.push(val);
+        self.left.push(None);
+        self.right.push(None);
+        self.parent.push(None);
+        let index = self.values.len() - 1;
+        if self.root.is_none() {
+            self.root = Some(index);
+        }
+        index
+    }
+
+    /// Replace the left child of `node`, detaching the previous one. `child` is moved away from
+    /// its previous parent. Moving the root below `node` makes the top ancestor of `node` the
+    /// root.
+    ///
+    /// # Panics
+    /// If `child` is `node` or one of its ancestors, which would make a cycle.
+    pub fn set_left(&mut self, node: usize, child: Option<usize>) {
+        self.set_child(node, child, true);
+    }
+
+    /// Replace the right child of `node`, like [`ArenaTree::set_left`].
+    ///
+    /// # Panics
+    /// If `child` is `node` or one of its ancestors, which would make a cycle.
+    pub fn set_right(&mut self, node: usize, child: Option<usize>) {
+        self.set_child(node, child, false);
+    }
+
+    fn set_child(&mut self, node: usize, child: Option<usize>, is_left: bool) {
+        if let Some(child) = child {
+            // a leaf can't be an ancestor, which saves the walk up when building top-down
+            let is_leaf = self.left[child].is_none() && self.right[child].is_none();
+            let mut ancestor = if is_leaf && child != node {
+                None
+            } else {
+                Some(node)
+            };
+            while let Some(a) = ancestor {
+                assert!(
+                    a != child,
+                    "node {} can't be a child of its descendant {}",
+                    child,
+                    node
+                );
+                ancestor = self.parent[a];
+            }
+            self.detach(child);
+            if self.root == Some(child) {
+                let mut top = node;
+                while let Some(p) = self.parent[top] {
+                    top = p;
+                }
+                self.root = Some(top);
+            }
+        }
+        let slot = if is_left {
+            &mut self.left[node]
+        } else {
+            &mut self.right[node]
+        };
+        if let Some(old) = std::mem::replace(slot, child) {
+            self.parent[old] = None;
+        }
+        if let Some(child) = child {
+            self.parent[child] = Some(node);
+        }
+    }
+
+    /// Remove `node` from the children of its parent.
+    fn detach(&mut self, node: usize) {
+        if let Some(parent) = self.parent[node].take() {
+            if self.left[parent] == Some(node) {
+                self.left[parent] = None;
+            }
+            if self.right[parent] == Some(node) {
+                self.right[parent] = None;
+            }
+        }
+    }
+
+    /// Copy a LeetCode tree, numbering the nodes in level order.
+    pub fn from_tree(root: &Option<Rc<RefCell<TreeNode>>>) -> Self {
+        let mut arena = ArenaTree::new();
+        let mut queue = VecDeque::new();
+        if let Some(root) = root {
+            queue.push_back((Rc::clone(root), None));
+        }
+        while let Some((node, parent)) = queue.pop_front() {
+            let n = node.borrow();
+            let index = arena.add_node(n.val);
+            match parent {
+                Some((parent, true)) => arena.set_left(parent, Some(index)),
+                Some((parent, false)) => arena.set_right(parent, Some(index)),
+                None => {}
+            }
+            if let Some(left) = &n.left {
+                queue.push_back((Rc::clone(left), Some((index, true))));
+            }
+            if let Some(right) = &n.right {
+                queue.push_back((Rc::clone(right), Some((index, false))));
+            }
+        }
+        arena
+    }
+
+    /// Build the LeetCode tree of the nodes reachable from the root.
+    pub fn to_tree(&self) -> Option<Rc<RefCell<TreeNode>>> {
+        let nodes: Vec<Rc<RefCell<TreeNode>>> = self
+            .values
+            .iter()
+            .map(|&v| Rc::new(RefCell::new(TreeNode::new(v))))
+            .collect();
+        for i in self.traverse(TraversalType::Preorder) {
+            let mut n = nodes[i].borrow_mut();
+            n.left = self.left[i].map(|l| Rc::clone(&nodes[l]));
+            n.right = self.right[i].map(|r| Rc::clone(&nodes[r]));
+        }
+        self.root.map(|r| Rc::clone(&nodes[r]))
+    }
+
+    /// Parse a LeetCode level order string, see [`TreeNode::from_string`].
+    pub fn from_string(s: &str) -> Result<Self, String> {
+        TreeNode::from_string(s).map(|tree| ArenaTree::from_tree(&tree))
+    }
+
+    pub fn to_leetcode_string(&self) -> String {
+        self.to_tree().to_leetcode_string()
+    }
+
+    /// Iterate over the nodes reachable from the root in the order `t`.
+    pub fn traverse(&self, t: TraversalType) -> ArenaTraversal<'_> {
+        ArenaTraversal {
+            tree: self,
+            order: t,
+            stack: self.root.iter().map(|&r| (r, false)).collect(),
+        }
+    }
+
+    pub fn walk<F: FnMut(usize)>(&self, t: TraversalType, func: F) {
+        self.traverse(t).for_each(func);
+    }
+
+    /// Compute a value for every subtree from the value of its root (`val_func`) and the values
+    /// of its subtrees (`aggr_func`), bottom up, like [`RefTreeNode::aggregate`].
+    pub fn aggregate<T, V, A>(&self, mut val_func: V, mut aggr_func: A) -> Option<T>
+    where
+        V: FnMut(usize) -> T,
+        A: FnMut(T, Option<T>, Option<T>) -> Option<T>,
+    {
+        let mut results: Vec<Option<T>> = (0..self.len()).map(|_| None).collect();
+        for i in self.traverse(TraversalType::Postorder) {
+            let lval = self.left[i].and_then(|l| results[l].take());
+            let rval = self.right[i].and_then(|r| results[r].take());
+            results[i] = aggr_func(val_func(i), lval, rval);
+        }
+        self.root.and_then(|r| results[r].take())
+    }
+
+    /// The depth of every node reachable from the root, 0 for the root.
+    pub fn depths(&self) -> Vec<usize> {
+        let mut depths = vec![0; self.len()];
+        for i in self.traverse(TraversalType::Preorder) {
+            if let Some(p) = self.parent[i] {
+                depths[i] = depths[p] + 1;
+            }
+        }
+        depths
+    }
+
+    /// The values of each row, from the root down.
+    pub fn levels(&self) -> Vec<Vec<i32>> {
+        let depths = self.depths();
+        let mut levels: Vec<Vec<i32>> = Vec::new();
+        for i in self.traverse(TraversalType::LevelOrder) {
+            if levels.len() == depths[i] {
+                levels.push(Vec::new());
+            }
+            levels[depths[i]].push(self.values[i]);
+        }
+        levels
+    }
+
+    /// The number of nodes on the longest path from the root down to a leaf.
+    pub fn height(&self) -> usize {
+        self.aggregate(|_| 1, |v, l, r| Some(v + l.max(r).unwrap_or(0)))
+            .unwrap_or(0)
+    }
+}
+
+/// The iterator of [`ArenaTree::traverse`].
+pub struct ArenaTraversal<'a> {
+    tree: &'a ArenaTree,
+    order: TraversalType,
+    /// The nodes to visit, with whether their children were already pushed. A stack, or a queue
+    /// for level order.
+    stack: VecDeque<(usize, bool)>,
+}
+
+impl<'a> Iterator for ArenaTraversal<'a> {
+    type Item = usize;
+    fn next(&mut self) -> Option<usize> {
+        loop {
+            let (node, expanded) = if self.order == TraversalType::LevelOrder {
+                self.stack.pop_front()?
+            } else {
+                self.stack.pop_back()?
+            };
+            if expanded {
+                return Some(node);
+            }
+            let left = self.tree.left[node].map(|l| (l, false));
+            let right = self.tree.right[node].map(|r| (r, false));
+            match self.order {
+                TraversalType::Preorder => {
+                    self.stack.extend(right);
+                    self.stack.extend(left);
+                    return Some(node);
+                }
+                TraversalType::Inorder => {
+                    self.stack.extend(right);
+                    self.stack.push_back((node, true));
+                    self.stack.extend(left);
+                }
+                TraversalType::Postorder => {
+                    self.stack.push_back((node, true));
+                    self.stack.extend(right);
+                    self.stack.extend(left);
+                }
+                TraversalType::LevelOrder => {
+                    self.stack.extend(left);
+                    self.stack.extend(right);
+                    return Some(node);
+                }
+            }
+        }
+    }
+}
+// #endregion
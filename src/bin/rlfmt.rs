@@ -1,47 +1,148 @@
-use cauly_rust_leetcode_utils::*;
+use cauly_rust_leetcode_utils::rlfmt::{
+    flatten_file, library_file, module_table, parse_args, parse_examples, scaffold,
+    solution_signatures, summary, verify, watched_files, which, Args, Flattened, Watcher, USAGE,
+};
+use cauly_rust_leetcode_utils::VERSION;
 use std::env;
 use std::fs;
 use std::path::Path;
+use std::process;
+use std::thread;
+use std::time::Duration;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
-    let filename;
-    if args.len() > 1 {
-        filename = args[1].clone();
-    } else if Path::new("lib.rs").is_file() {
-        filename = "lib.rs".to_string();
-    } else if Path::new("src/lib.rs").is_file() {
-        filename = "src/lib.rs".to_string();
+    let args = match parse_args(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("rlfmt: error: {}\n\n{}", e, USAGE);
+            process::exit(2);
+        }
+    };
+    if args.help {
+        print!("{}", USAGE);
+        return;
+    }
+    if args.version {
+        println!("rlfmt {}", VERSION);
+        return;
+    }
+    if args.list {
+        print!("{}", module_table());
+        return;
+    }
+    if let Some(item) = &args.which {
+        match which(item) {
+            Ok(import) => println!("{}", import),
+            Err(e) => {
+                eprintln!("rlfmt: error: {}", e);
+                process::exit(1);
+            }
+        }
+        return;
+    }
+    let result = if let Some(examples) = &args.scaffold {
+        run_scaffold(&args, examples).map(|_| true)
+    } else if args.watch {
+        watch(&args)
     } else {
-        panic!("File name not specified, and no default lib.rs found.");
+        run(&args).map(|(ok, _)| ok)
+    };
+    match result {
+        Ok(true) => {}
+        Ok(false) => process::exit(1),
+        Err(e) => {
+            eprintln!("rlfmt: error: {}", e);
+            process::exit(1);
+        }
     }
+}
 
-    let content = fs::read_to_string(filename).unwrap();
-    let mut result = String::new();
+/// Flatten the input once. Returns false if the verification found errors.
+fn run(args: &Args) -> Result<(bool, Flattened), String> {
+    let path = args.input_file()?;
+    let filename = path.display().to_string();
+    let flattened = flatten_file(&path, &args.options)?;
+    match &args.output {
+        Some(output) => fs::write(output, format!("{}\n", flattened.code))
+            .map_err(|e| format!("can't write `{}`: {}", output, e))?,
+        None => println!("{}", flattened.code),
+    }
 
-    for line in content.lines() {
-        if line.trim_start().starts_with("pub struct Solution;")
-            || line.trim_start().starts_with("struct Solution;")
-        {
-            // skip
-        } else if line.trim_start().starts_with("print!")
-            || line.trim_start().starts_with("println!")
-        {
-            result.push_str("//");
-            result.push_str(line);
-            result.push_str("\n");
-        } else if line
-            .trim_start()
-            .starts_with("use cauly_rust_leetcode_utils::")
-        {
-            let remaining = line["use cauly_rust_leetcode_utils::".len()..].to_string();
-            result.push_str("// These code are written and formatted by cauly-rust-leetcode-utils. See `https://github.com/CaulyKan/cauly-rust-leetcode-utils` for more infomation.\n");
-            result.push_str(get_module_source(remaining));
-        } else {
-            result.push_str(line);
-            result.push_str("\n");
+    if args.verify {
+        let diagnostics = verify(&flattened, &filename)?;
+        for diagnostic in &diagnostics {
+            eprintln!("{}", diagnostic);
+        }
+        if diagnostics.iter().any(|d| d.level == "error") {
+            return Ok((false, flattened));
         }
+        eprintln!("rlfmt: {} compiles.", filename);
     }
+    Ok((true, flattened))
+}
+
+/// Write the tests for the examples in the file `examples`.
+fn run_scaffold(args: &Args, examples: &str) -> Result<(), String> {
+    let text =
+        fs::read_to_string(examples).map_err(|e| format!("can't read `{}`: {}", examples, e))?;
+    let path = args.input_file()?;
+    let source =
+        fs::read_to_string(&path).map_err(|e| format!("can't read `{}`: {}", path.display(), e))?;
+    let signatures = solution_signatures(&source);
+    if signatures.is_empty() {
+        return Err(format!("no `impl Solution` found in `{}`", path.display()));
+    }
+    let tests = scaffold(&parse_examples(&text)?, &signatures)?;
+    match &args.output {
+        Some(output) => {
+            fs::write(output, tests).map_err(|e| format!("can't write `{}`: {}", output, e))
+        }
+        None => {
+            print!("{}", tests);
+            Ok(())
+        }
+    }
+}
+
+/// Flatten the input every time it or one of the files it is made of changes. Only returns if
+/// there is no input file to watch.
+fn watch(args: &Args) -> Result<bool, String> {
+    let path = args.input_file()?;
+    let mut files = vec![path.clone()];
+    loop {
+        match run(args) {
+            Ok((_, flattened)) => {
+                eprintln!("rlfmt: {}: {}", path.display(), summary(&flattened));
+                files = watched_files(&path, &flattened);
+            }
+            Err(e) => eprintln!("rlfmt: error: {}", e),
+        }
+        eprintln!(
+            "rlfmt: watching {} file(s), press Ctrl-C to stop",
+            files.len()
+        );
+        let mut watcher = Watcher::new(files.clone());
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            let changed = watcher.changed();
+            let (library, local): (Vec<_>, Vec<_>) =
+                changed.iter().partition(|f| is_library_file(f));
+            for file in library {
+                eprintln!(
+                    "rlfmt: {} changed, reinstall rlfmt for it to be inlined",
+                    file.display()
+                );
+            }
+            if !local.is_empty() {
+                break;
+            }
+        }
+    }
+}
 
-    println!("{}", result);
+fn is_library_file(file: &Path) -> bool {
+    file.file_stem()
+        .is_some_and(|stem| library_file(&stem.to_string_lossy()) == file)
 }
@@ -1,4 +1,6 @@
 use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::rc::Rc;
 
 // #region LeetCode TreeNode
@@ -28,6 +30,233 @@ pub enum TraversalType {
     Inorder,
     Preorder,
     Postorder,
+    /// Breadth first, row by row from left to right.
+    LevelOrder,
+}
+
+#[derive(PartialEq, Copy, Clone, Debug, Default)]
+pub enum TreeLayout {
+    /// The root on top, e.g. `" 1\n┌┴┐\n2 3\n"`.
+    #[default]
+    TopDown,
+    /// The root on the left, right subtrees above and left subtrees below it.
+    Sideways,
+}
+
+/// Options of [`RefTreeNode::draw`].
+#[derive(Clone, Debug, Default)]
+pub struct DrawOptions {
+    pub layout: TreeLayout,
+    /// Only use ASCII characters instead of box drawing ones.
+    pub ascii: bool,
+    /// Nodes drawn in brackets, e.g. `[5]`, compared by pointer.
+    pub marked: Vec<Rc<RefCell<TreeNode>>>,
+}
+
+/// A node met by [`RefTreeNode::traverse`].
+#[derive(Debug, Clone)]
+pub struct TreeVisit {
+    pub node: Rc<RefCell<TreeNode>>,
+    /// 0 for the root.
+    pub depth: usize,
+    pub parent: Option<Rc<RefCell<TreeNode>>>,
+}
+
+/// The iterator of [`RefTreeNode::traverse`].
+pub struct Traversal {
+    order: TraversalType,
+    /// The nodes to visit, with whether their children were already pushed. A stack, or a queue
+    /// for level order.
+    stack: VecDeque<(TreeVisit, bool)>,
+}
+
+impl Iterator for Traversal {
+    type Item = TreeVisit;
+    fn next(&mut self) -> Option<TreeVisit> {
+        loop {
+            let (visit, expanded) = if self.order == TraversalType::LevelOrder {
+                self.stack.pop_front()?
+            } else {
+                self.stack.pop_back()?
+            };
+            if expanded {
+                return Some(visit);
+            }
+            let child = |child: &Option<Rc<RefCell<TreeNode>>>| {
+                child.as_ref().map(|c| {
+                    let child = TreeVisit {
+                        node: Rc::clone(c),
+                        depth: visit.depth + 1,
+                        parent: Some(Rc::clone(&visit.node)),
+                    };
+                    (child, false)
+                })
+            };
+            let (left, right) = {
+                let n = visit.node.borrow();
+                (child(&n.left), child(&n.right))
+            };
+            match self.order {
+                TraversalType::Preorder => {
+                    self.stack.extend(right);
+                    self.stack.extend(left);
+                    return Some(visit);
+                }
+                TraversalType::Inorder => {
+                    self.stack.extend(right);
+                    self.stack.push_back((visit, true));
+                    self.stack.extend(left);
+                }
+                TraversalType::Postorder => {
+                    self.stack.push_back((visit, true));
+                    self.stack.extend(right);
+                    self.stack.extend(left);
+                }
+                TraversalType::LevelOrder => {
+                    self.stack.extend(left);
+                    self.stack.extend(right);
+                    return Some(visit);
+                }
+            }
+        }
+    }
+}
+
+/// The iterator of [`RefTreeNode::levels`].
+pub struct Levels {
+    row: Vec<Rc<RefCell<TreeNode>>>,
+}
+
+impl Iterator for Levels {
+    type Item = Vec<i32>;
+    fn next(&mut self) -> Option<Vec<i32>> {
+        if self.row.is_empty() {
+            return None;
+        }
+        let mut values = Vec::with_capacity(self.row.len());
+        let mut next_row = Vec::new();
+        for node in &self.row {
+            let n = node.borrow();
+            values.push(n.val);
+            next_row.extend(n.left.iter().cloned());
+            next_row.extend(n.right.iter().cloned());
+        }
+        self.row = next_row;
+        Some(values)
+    }
+}
+
+/// The values of a binary search tree in ascending order, keeping only the path to the next
+/// node, like LeetCode's `BSTIterator`.
+///
+/// # Example
+/// ```
+/// use cauly_rust_leetcode_utils::binary_tree::*;
+/// let mut iter = BSTIterator::new(TreeNode::from_string("[7,3,15,null,null,9,20]").unwrap());
+/// assert_eq!(Some(3), iter.next());
+/// assert_eq!(Some(7), iter.next());
+/// assert!(iter.has_next());
+/// assert_eq!(vec![9, 15, 20], iter.collect::<Vec<_>>());
+/// ```
+pub struct BSTIterator {
+    stack: Vec<Rc<RefCell<TreeNode>>>,
+}
+
+impl BSTIterator {
+    pub fn new(root: Option<Rc<RefCell<TreeNode>>>) -> Self {
+        let mut iter = BSTIterator { stack: Vec::new() };
+        iter.push_left(root);
+        iter
+    }
+
+    pub fn has_next(&self) -> bool {
+        !self.stack.is_empty()
+    }
+
+    fn push_left(&mut self, mut node: Option<Rc<RefCell<TreeNode>>>) {
+        while let Some(n) = node {
+            node = n.borrow().left.clone();
+            self.stack.push(n);
+        }
+    }
+}
+
+impl Iterator for BSTIterator {
+    type Item = i32;
+    fn next(&mut self) -> Option<i32> {
+        let node = self.stack.pop()?;
+        let n = node.borrow();
+        self.push_left(n.right.clone());
+        Some(n.val)
+    }
+}
+
+/// Numbers subtrees by their shape and values, so that equal subtrees get equal ids, also
+/// across the trees numbered by the same `SubtreeIds`. The empty tree is 0.
+///
+/// # Example
+/// ```
+/// use cauly_rust_leetcode_utils::binary_tree::*;
+/// let mut ids = SubtreeIds::new();
+/// let tree = TreeNode::from_string("[1,2,3,4,null,2,4,null,null,4]").unwrap();
+/// let numbered: Vec<_> = ids.number(&tree).into_iter().map(|(n, id)| (n.borrow().val, id)).collect();
+/// assert_eq!(vec![(4, 1), (2, 2), (4, 1), (2, 2), (4, 1), (3, 3), (1, 4)], numbered);
+/// assert_eq!(2, ids.id(&TreeNode::from_string("[2,4]").unwrap()));
+/// let mut ids = SubtreeIds::with_flips();
+/// assert_eq!(ids.id(&TreeNode::from_string("[1,2,3]").unwrap()), ids.id(&TreeNode::from_string("[1,3,2]").unwrap()));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SubtreeIds {
+    /// The ids by the value of the root and the ids of the children.
+    ids: HashMap<(i32, usize, usize), usize>,
+    flips: bool,
+}
+
+impl SubtreeIds {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Subtrees which only differ by swapping the children of some nodes get equal ids.
+    pub fn with_flips() -> Self {
+        SubtreeIds {
+            flips: true,
+            ..Default::default()
+        }
+    }
+
+    /// The id of every subtree, in postorder.
+    pub fn number(
+        &mut self,
+        tree: &Option<Rc<RefCell<TreeNode>>>,
+    ) -> Vec<(Rc<RefCell<TreeNode>>, usize)> {
+        let mut result = Vec::new();
+        // the ids of the subtrees whose parent isn't visited yet, left before right
+        let mut pending: Vec<usize> = Vec::new();
+        for visit in tree.traverse(TraversalType::Postorder) {
+            let (val, has_left, has_right) = {
+                let n = visit.node.borrow();
+                (n.val, n.left.is_some(), n.right.is_some())
+            };
+            let r = if has_right { pending.pop().unwrap() } else { 0 };
+            let l = if has_left { pending.pop().unwrap() } else { 0 };
+            let key = if self.flips {
+                (val, l.min(r), l.max(r))
+            } else {
+                (val, l, r)
+            };
+            let next = self.ids.len() + 1;
+            let id = *self.ids.entry(key).or_insert(next);
+            pending.push(id);
+            result.push((visit.node, id));
+        }
+        result
+    }
+
+    /// The id of the whole tree.
+    pub fn id(&mut self, tree: &Option<Rc<RefCell<TreeNode>>>) -> usize {
+        self.number(tree).last().map_or(0, |&(_, id)| id)
+    }
 }
 
 pub trait RefTreeNode {
@@ -40,24 +269,173 @@ pub trait RefTreeNode {
         left: &Option<Rc<RefCell<TreeNode>>>,
         right: &Option<Rc<RefCell<TreeNode>>>,
     );
-    fn walk(&self, t: TraversalType, func: fn(Option<Rc<RefCell<TreeNode>>>));
-    fn walk_t<T>(
-        &self,
-        t: TraversalType,
-        result: &mut T,
-        func: fn(&mut T, Option<Rc<RefCell<TreeNode>>>),
-    );
-    fn aggregate<T>(
-        &self,
-        val_func: fn(Option<Rc<RefCell<TreeNode>>>) -> T,
-        aggr_func: fn(T, Option<T>, Option<T>) -> Option<T>,
-    ) -> Option<T>;
-    fn aggregate_t<R, T>(
+    /// Call `func` with every node, in the order `t`. The nodes are visited with an explicit
+    /// stack, so deep trees don't overflow; use [`traverse`](RefTreeNode::traverse) to stop early.
+    fn walk<F>(&self, t: TraversalType, func: F)
+    where
+        F: FnMut(Option<Rc<RefCell<TreeNode>>>);
+    fn walk_t<T, F>(&self, t: TraversalType, result: &mut T, func: F)
+    where
+        F: FnMut(&mut T, Option<Rc<RefCell<TreeNode>>>);
+    /// Compute a value for every subtree from the value of its root (`val_func`) and the values
+    /// of its subtrees (`aggr_func`), bottom up without recursion.
+    fn aggregate<T, V, A>(&self, val_func: V, aggr_func: A) -> Option<T>
+    where
+        V: FnMut(Option<Rc<RefCell<TreeNode>>>) -> T,
+        A: FnMut(T, Option<T>, Option<T>) -> Option<T>;
+    fn aggregate_t<R, T, V, A>(&self, result: &mut R, val_func: V, aggr_func: A) -> Option<T>
+    where
+        V: FnMut(&mut R, Option<Rc<RefCell<TreeNode>>>) -> T,
+        A: FnMut(&mut R, T, Option<T>, Option<T>) -> Option<T>;
+    /// Iterate over the nodes in the order `t`, with their depth and parent.
+    ///
+    /// # Example
+    /// ```
+    /// use cauly_rust_leetcode_utils::binary_tree::*;
+    /// let tree = TreeNode::from_string("[1,2,3,4]").unwrap();
+    /// let inorder: Vec<_> = tree.traverse(TraversalType::Inorder).map(|v| (v.node.borrow().val, v.depth)).collect();
+    /// assert_eq!(vec![(4, 2), (2, 1), (1, 0), (3, 1)], inorder);
+    /// let parent = tree.traverse(TraversalType::Preorder).find(|v| v.node.borrow().val == 4).unwrap().parent;
+    /// assert_eq!(Some(2), parent.get_val());
+    /// ```
+    fn traverse(&self, t: TraversalType) -> Traversal;
+    /// Iterate over the values of each row, from the root down.
+    ///
+    /// # Example
+    /// ```
+    /// use cauly_rust_leetcode_utils::binary_tree::*;
+    /// let tree = TreeNode::from_string("[3,9,20,null,null,15,7]").unwrap();
+    /// assert_eq!(vec![vec![3], vec![9, 20], vec![15, 7]], tree.levels().collect::<Vec<_>>());
+    /// assert_eq!(vec![vec![3], vec![20, 9], vec![15, 7]], tree.zigzag_levels());
+    /// assert_eq!(vec![vec![9], vec![3, 15], vec![20], vec![7]], tree.vertical_order());
+    /// ```
+    fn levels(&self) -> Levels;
+    /// The rows, alternately from left to right and from right to left.
+    fn zigzag_levels(&self) -> Vec<Vec<i32>>;
+    /// The last value of each row.
+    fn right_view(&self) -> Vec<i32>;
+    /// The first value of each row.
+    fn left_view(&self) -> Vec<i32>;
+    /// The columns from left to right, each from top to bottom and then from left to right.
+    fn vertical_order(&self) -> Vec<Vec<i32>>;
+    fn level_max(&self) -> Vec<i32>;
+    fn level_sum(&self) -> Vec<i64>;
+    fn level_average(&self) -> Vec<f64>;
+    /// The LeetCode level order form, e.g. `[1,null,2,3]`, without trailing nulls.
+    fn to_leetcode_string(&self) -> String;
+    /// Draw the tree, one line per row of nodes, for debugging or assertion messages. The empty
+    /// tree is an empty string. Drawings get large for deep trees, as lines get longer with the
+    /// depth: the sideways layout is the one to use beyond a few hundred levels.
+    ///
+    /// # Example
+    /// ```
+    /// use cauly_rust_leetcode_utils::binary_tree::*;
+    /// let tree = TreeNode::from_string("[1,2,3,null,-4]").unwrap();
+    /// assert_eq!("   1\n┌──┴┐\n2   3\n└─┐\n -4\n", tree.draw(&Default::default()));
+    /// let options = DrawOptions {
+    ///     layout: TreeLayout::Sideways,
+    ///     ascii: true,
+    ///     marked: vec![tree.get_right().unwrap()],
+    /// };
+    /// assert_eq!("+-- [3]\n1\n|   +-- -4\n+-- 2\n", tree.draw(&options));
+    /// ```
+    fn draw(&self, options: &DrawOptions) -> String;
+    /// Insert `val` into a binary search tree. Returns false if it is already there.
+    ///
+    /// # Example
+    /// ```
+    /// use cauly_rust_leetcode_utils::binary_tree::*;
+    /// let mut tree = None;
+    /// for v in [5, 3, 8, 4] {
+    ///     tree.bst_insert(v);
+    /// }
+    /// assert_eq!("[5,3,8,null,4]", tree.to_leetcode_string());
+    /// assert!(tree.bst_delete(5));
+    /// assert_eq!("[8,3,null,null,4]", tree.to_leetcode_string());
+    /// assert_eq!((Some(4), Some(8)), (tree.bst_floor(7), tree.bst_ceil(7)));
+    /// ```
+    fn bst_insert(&mut self, val: i32) -> bool;
+    /// Delete `val` from a binary search tree, replacing a node with two children by its inorder
+    /// successor. Returns false if it isn't there.
+    fn bst_delete(&mut self, val: i32) -> bool;
+    fn bst_search(&self, val: i32) -> Option<Rc<RefCell<TreeNode>>>;
+    /// The greatest value `<= val`.
+    fn bst_floor(&self, val: i32) -> Option<i32>;
+    /// The least value `>= val`.
+    fn bst_ceil(&self, val: i32) -> Option<i32>;
+    /// The k-th smallest value, counting from 1.
+    fn bst_kth_smallest(&self, k: usize) -> Option<i32>;
+    /// Every node is greater than its left subtree and less than its right subtree.
+    fn is_valid_bst(&self) -> bool;
+    /// The sum of the values in `[lo, hi]`.
+    fn bst_range_sum(&self, lo: i32, hi: i32) -> i64;
+    /// The number of values in `[lo, hi]`.
+    fn bst_range_count(&self, lo: i32, hi: i32) -> usize;
+    /// The values in ascending order, see [`BSTIterator`].
+    fn bst_iter(&self) -> BSTIterator;
+    /// The number of nodes on the longest path from the root down to a leaf.
+    ///
+    /// # Example
+    /// ```
+    /// use cauly_rust_leetcode_utils::binary_tree::*;
+    /// let tree = TreeNode::from_string("[-10,9,20,null,null,15,7]").unwrap();
+    /// assert_eq!((3, 5, 3), (tree.height(), tree.node_count(), tree.diameter()));
+    /// assert_eq!(Some(42), tree.max_path_sum());
+    /// assert_eq!(Some(20), tree.lca_by_val(15, 7).get_val());
+    /// assert_eq!(vec![vec![-10, 9], vec![-10, 20, 15], vec![-10, 20, 7]], tree.root_to_leaf_paths());
+    /// ```
+    fn height(&self) -> usize;
+    fn node_count(&self) -> usize;
+    /// The number of edges on the longest path between two nodes.
+    fn diameter(&self) -> usize;
+    /// The greatest sum of the values on a path between two nodes, or None for the empty tree.
+    fn max_path_sum(&self) -> Option<i64>;
+    /// The lowest common ancestor of two nodes of the tree, compared by pointer.
+    fn lca(
         &self,
-        result: &mut R,
-        val_func: fn(&mut R, Option<Rc<RefCell<TreeNode>>>) -> T,
-        aggr_func: fn(&mut R, T, Option<T>, Option<T>) -> Option<T>,
-    ) -> Option<T>;
+        p: &Rc<RefCell<TreeNode>>,
+        q: &Rc<RefCell<TreeNode>>,
+    ) -> Option<Rc<RefCell<TreeNode>>>;
+    /// The lowest common ancestor of the first nodes in preorder with the values `p` and `q`.
+    fn lca_by_val(&self, p: i32, q: i32) -> Option<Rc<RefCell<TreeNode>>>;
+    /// The nodes from the root down to `target`, or None if it isn't in the tree.
+    fn path_to(&self, target: &Rc<RefCell<TreeNode>>) -> Option<Vec<Rc<RefCell<TreeNode>>>>;
+    /// The values on every path from the root to a leaf, from left to right.
+    fn root_to_leaf_paths(&self) -> Vec<Vec<i32>>;
+    /// The parent of every node but the root, keyed by [`Rc::as_ptr`] of the node.
+    fn parent_map(&self) -> HashMap<*const RefCell<TreeNode>, Rc<RefCell<TreeNode>>>;
+    /// The values of the nodes `k` edges away from `target`, nearest first.
+    fn distance_k(&self, target: &Rc<RefCell<TreeNode>>, k: usize) -> Vec<i32>;
+    /// The first difference from `expected` in preorder, by the path to it.
+    ///
+    /// # Example
+    /// ```
+    /// use cauly_rust_leetcode_utils::binary_tree::*;
+    /// let tree = TreeNode::from_string("[1,2,3,null,4]").unwrap();
+    /// assert_eq!(Ok(()), tree.compare(&TreeNode::from_string("[1,2,3,null,4]").unwrap()));
+    /// assert_eq!(
+    ///     Err("root.left.right: expected 5, got 4".to_string()),
+    ///     tree.compare(&TreeNode::from_string("[1,2,3,null,5]").unwrap())
+    /// );
+    /// assert_eq!(
+    ///     Err("root.right.left: expected 6, got null".to_string()),
+    ///     tree.compare(&TreeNode::from_string("[1,2,3,null,4,6]").unwrap())
+    /// );
+    /// assert!(tree.is_subtree(&TreeNode::from_string("[2,null,4]").unwrap()));
+    /// assert!(tree.is_isomorphic(&TreeNode::from_string("[1,3,2,null,null,4]").unwrap()));
+    /// assert!(TreeNode::from_string("[1,2,2,3,null,null,3]").unwrap().is_symmetric());
+    /// ```
+    fn compare(&self, expected: &Self) -> Result<(), String>;
+    /// Whether `sub` is equal to the subtree of some node. The empty tree is a subtree of every
+    /// tree.
+    fn is_subtree(&self, sub: &Self) -> bool;
+    /// Whether `other` is the mirror image of this tree.
+    fn is_mirror(&self, other: &Self) -> bool;
+    fn is_symmetric(&self) -> bool;
+    /// Whether `other` can be made equal to this tree by swapping the children of some nodes.
+    fn is_isomorphic(&self, other: &Self) -> bool;
+    /// A node of each subtree that occurs more than once: its second occurrence in postorder.
+    fn duplicate_subtrees(&self) -> Vec<Rc<RefCell<TreeNode>>>;
 }
 
 impl RefTreeNode for Option<Rc<RefCell<TreeNode>>> {
@@ -110,148 +488,975 @@ impl RefTreeNode for Option<Rc<RefCell<TreeNode>>> {
             };
         }
     }
-    fn walk(&self, t: TraversalType, func: fn(Option<Rc<RefCell<TreeNode>>>)) {
-        if let Some(node) = self {
-            if t == TraversalType::Preorder {
-                func(Some(Rc::clone(&node)));
-            }
-            {
-                let n = node.borrow();
-                if let Some(left) = &n.left {
-                    Some(Rc::clone(left)).walk(t, func);
+    fn walk<F>(&self, t: TraversalType, mut func: F)
+    where
+        F: FnMut(Option<Rc<RefCell<TreeNode>>>),
+    {
+        for visit in self.traverse(t) {
+            func(Some(visit.node));
+        }
+    }
+    fn walk_t<T, F>(&self, t: TraversalType, result: &mut T, mut func: F)
+    where
+        F: FnMut(&mut T, Option<Rc<RefCell<TreeNode>>>),
+    {
+        for visit in self.traverse(t) {
+            func(result, Some(visit.node));
+        }
+    }
+    fn aggregate<T, V, A>(&self, mut val_func: V, mut aggr_func: A) -> Option<T>
+    where
+        V: FnMut(Option<Rc<RefCell<TreeNode>>>) -> T,
+        A: FnMut(T, Option<T>, Option<T>) -> Option<T>,
+    {
+        self.aggregate_t(
+            &mut (),
+            |_, node| val_func(node),
+            |_, val, lval, rval| aggr_func(val, lval, rval),
+        )
+    }
+    fn aggregate_t<R, T, V, A>(
+        &self,
+        result: &mut R,
+        mut val_func: V,
+        mut aggr_func: A,
+    ) -> Option<T>
+    where
+        V: FnMut(&mut R, Option<Rc<RefCell<TreeNode>>>) -> T,
+        A: FnMut(&mut R, T, Option<T>, Option<T>) -> Option<T>,
+    {
+        // the values of the subtrees whose parent isn't visited yet, left before right
+        let mut values: Vec<Option<T>> = Vec::new();
+        for visit in self.traverse(TraversalType::Postorder) {
+            let (has_left, has_right) = {
+                let n = visit.node.borrow();
+                (n.left.is_some(), n.right.is_some())
+            };
+            let rval = if has_right {
+                values.pop().flatten()
+            } else {
+                None
+            };
+            let lval = if has_left {
+                values.pop().flatten()
+            } else {
+                None
+            };
+            let val = val_func(result, Some(visit.node));
+            values.push(aggr_func(result, val, lval, rval));
+        }
+        values.pop().flatten()
+    }
+    fn traverse(&self, t: TraversalType) -> Traversal {
+        let stack = self
+            .iter()
+            .map(|node| {
+                let visit = TreeVisit {
+                    node: Rc::clone(node),
+                    depth: 0,
+                    parent: None,
+                };
+                (visit, false)
+            })
+            .collect();
+        Traversal { order: t, stack }
+    }
+    fn levels(&self) -> Levels {
+        Levels {
+            row: self.iter().cloned().collect(),
+        }
+    }
+    fn zigzag_levels(&self) -> Vec<Vec<i32>> {
+        self.levels()
+            .enumerate()
+            .map(|(i, mut row)| {
+                if i % 2 == 1 {
+                    row.reverse();
                 }
+                row
+            })
+            .collect()
+    }
+    fn right_view(&self) -> Vec<i32> {
+        self.levels()
+            .filter_map(|row| row.last().cloned())
+            .collect()
+    }
+    fn left_view(&self) -> Vec<i32> {
+        self.levels()
+            .filter_map(|row| row.first().cloned())
+            .collect()
+    }
+    fn vertical_order(&self) -> Vec<Vec<i32>> {
+        let mut columns: BTreeMap<i32, Vec<i32>> = BTreeMap::new();
+        let mut queue: VecDeque<(Rc<RefCell<TreeNode>>, i32)> =
+            self.iter().map(|n| (Rc::clone(n), 0)).collect();
+        while let Some((node, column)) = queue.pop_front() {
+            let n = node.borrow();
+            columns.entry(column).or_default().push(n.val);
+            if let Some(left) = &n.left {
+                queue.push_back((Rc::clone(left), column - 1));
             }
-            if t == TraversalType::Inorder {
-                func(Some(Rc::clone(&node)));
+            if let Some(right) = &n.right {
+                queue.push_back((Rc::clone(right), column + 1));
             }
-            {
-                let n = node.borrow();
-                if let Some(right) = &n.right {
-                    Some(Rc::clone(right)).walk(t, func);
+        }
+        columns.into_values().collect()
+    }
+    fn level_max(&self) -> Vec<i32> {
+        self.levels()
+            .filter_map(|row| row.into_iter().max())
+            .collect()
+    }
+    fn level_sum(&self) -> Vec<i64> {
+        self.levels()
+            .map(|row| row.into_iter().map(i64::from).sum())
+            .collect()
+    }
+    fn level_average(&self) -> Vec<f64> {
+        self.levels()
+            .map(|row| row.iter().map(|&v| v as f64).sum::<f64>() / row.len() as f64)
+            .collect()
+    }
+    fn to_leetcode_string(&self) -> String {
+        let mut items = Vec::new();
+        let mut row = vec![self.clone()];
+        while !row.is_empty() {
+            let mut new_row = Vec::new();
+            for node in row {
+                match node {
+                    Some(n) => {
+                        let n = n.borrow();
+                        items.push(n.val.to_string());
+                        new_row.push(n.left.clone());
+                        new_row.push(n.right.clone());
+                    }
+                    None => items.push("null".to_string()),
                 }
             }
-            if t == TraversalType::Postorder {
-                func(Some(Rc::clone(&node)));
+            row = new_row;
+        }
+        while items.last().map(|s| s.as_str()) == Some("null") {
+            items.pop();
+        }
+        format!("[{}]", items.join(","))
+    }
+    fn draw(&self, options: &DrawOptions) -> String {
+        let node = match self {
+            Some(node) => node,
+            None => return String::new(),
+        };
+        match options.layout {
+            TreeLayout::TopDown => {
+                let (lines, _) = self
+                    .aggregate(
+                        |node| {
+                            let label = node_label(&node.unwrap(), options);
+                            (vec![label.chars().collect()], 0)
+                        },
+                        |(mut label, _), left, right| {
+                            Some(draw_top_down(label.remove(0), left, right, options))
+                        },
+                    )
+                    .unwrap();
+                let mut result = String::new();
+                for line in lines {
+                    let line: String = line.into_iter().collect();
+                    result.push_str(line.trim_end());
+                    result.push('\n');
+                }
+                result
             }
+            TreeLayout::Sideways => draw_sideways(node, options),
         }
     }
-    fn walk_t<T>(
-        &self,
-        t: TraversalType,
-        result: &mut T,
-        func: fn(&mut T, Option<Rc<RefCell<TreeNode>>>),
-    ) {
-        if let Some(node) = self {
-            if t == TraversalType::Preorder {
-                func(result, Some(Rc::clone(&node)));
+    fn bst_insert(&mut self, val: i32) -> bool {
+        let mut current = match self {
+            Some(node) => Rc::clone(node),
+            None => {
+                *self = Some(tree_node(val));
+                return true;
             }
-            {
-                let n = node.borrow();
-                if let Some(left) = &n.left {
-                    Some(Rc::clone(left)).walk_t(t, result, func);
+        };
+        loop {
+            let next = {
+                let mut n = current.borrow_mut();
+                let child = match val.cmp(&n.val) {
+                    Ordering::Equal => return false,
+                    Ordering::Less => &mut n.left,
+                    Ordering::Greater => &mut n.right,
+                };
+                match child {
+                    Some(child) => Rc::clone(child),
+                    None => {
+                        *child = Some(tree_node(val));
+                        return true;
+                    }
                 }
+            };
+            current = next;
+        }
+    }
+    fn bst_delete(&mut self, val: i32) -> bool {
+        let mut parent = None;
+        let mut current = self.clone();
+        while let Some(node) = current.clone() {
+            let n = node.borrow();
+            current = match val.cmp(&n.val) {
+                Ordering::Equal => break,
+                Ordering::Less => n.left.clone(),
+                Ordering::Greater => n.right.clone(),
+            };
+            parent = Some(Rc::clone(&node));
+        }
+        let node = match current {
+            Some(node) => node,
+            None => return false,
+        };
+        let right = node.borrow().right.clone();
+        // the node to unlink, which has at most one child, and its parent
+        let (target, parent) = match right {
+            Some(right) if node.borrow().left.is_some() => {
+                let (mut successor, mut successor_parent) = (right, Rc::clone(&node));
+                loop {
+                    let left = successor.borrow().left.clone();
+                    match left {
+                        Some(left) => {
+                            successor_parent = successor;
+                            successor = left;
+                        }
+                        None => break,
+                    }
+                }
+                node.borrow_mut().val = successor.borrow().val;
+                (successor, Some(successor_parent))
             }
-            if t == TraversalType::Inorder {
-                func(result, Some(Rc::clone(&node)));
-            }
-            {
-                let n = node.borrow();
-                if let Some(right) = &n.right {
-                    Some(Rc::clone(right)).walk_t(t, result, func);
+            _ => (node, parent),
+        };
+        let child = {
+            let mut t = target.borrow_mut();
+            t.left.take().or_else(|| t.right.take())
+        };
+        match parent {
+            Some(parent) => {
+                let mut p = parent.borrow_mut();
+                if p.left.as_ref().is_some_and(|l| Rc::ptr_eq(l, &target)) {
+                    p.left = child;
+                } else {
+                    p.right = child;
                 }
             }
-            if t == TraversalType::Postorder {
-                func(result, Some(Rc::clone(&node)));
+            None => *self = child,
+        }
+        true
+    }
+    fn bst_search(&self, val: i32) -> Option<Rc<RefCell<TreeNode>>> {
+        let mut current = self.clone();
+        while let Some(node) = current {
+            let n = node.borrow();
+            current = match val.cmp(&n.val) {
+                Ordering::Equal => return Some(Rc::clone(&node)),
+                Ordering::Less => n.left.clone(),
+                Ordering::Greater => n.right.clone(),
+            };
+        }
+        None
+    }
+    fn bst_floor(&self, val: i32) -> Option<i32> {
+        let mut result = None;
+        let mut current = self.clone();
+        while let Some(node) = current {
+            let n = node.borrow();
+            if n.val <= val {
+                result = Some(n.val);
+                current = n.right.clone();
+            } else {
+                current = n.left.clone();
+            }
+        }
+        result
+    }
+    fn bst_ceil(&self, val: i32) -> Option<i32> {
+        let mut result = None;
+        let mut current = self.clone();
+        while let Some(node) = current {
+            let n = node.borrow();
+            if n.val >= val {
+                result = Some(n.val);
+                current = n.left.clone();
+            } else {
+                current = n.right.clone();
             }
         }
+        result
     }
-    fn aggregate<T>(
-        &self,
-        val_func: fn(Option<Rc<RefCell<TreeNode>>>) -> T,
-        aggr_func: fn(T, Option<T>, Option<T>) -> Option<T>,
-    ) -> Option<T> {
-        if let Some(node) = self {
+    fn bst_kth_smallest(&self, k: usize) -> Option<i32> {
+        self.bst_iter().nth(k.checked_sub(1)?)
+    }
+    fn is_valid_bst(&self) -> bool {
+        // nodes with the exclusive bounds of their values
+        let mut stack: Vec<(Rc<RefCell<TreeNode>>, i64, i64)> = self
+            .iter()
+            .map(|n| (Rc::clone(n), i64::MIN, i64::MAX))
+            .collect();
+        while let Some((node, lo, hi)) = stack.pop() {
             let n = node.borrow();
-            let mut lval = None;
-            let mut rval = None;
+            let val = i64::from(n.val);
+            if val <= lo || val >= hi {
+                return false;
+            }
             if let Some(left) = &n.left {
-                lval = Some(Rc::clone(left)).aggregate(val_func, aggr_func);
+                stack.push((Rc::clone(left), lo, val));
             }
             if let Some(right) = &n.right {
-                rval = Some(Rc::clone(right)).aggregate(val_func, aggr_func);
+                stack.push((Rc::clone(right), val, hi));
             }
-            let val = val_func(Some(Rc::clone(&node)));
-            aggr_func(val, lval, rval)
-        } else {
-            None
         }
+        true
+    }
+    fn bst_range_sum(&self, lo: i32, hi: i32) -> i64 {
+        bst_range(self, lo, hi).map(i64::from).sum()
+    }
+    fn bst_range_count(&self, lo: i32, hi: i32) -> usize {
+        bst_range(self, lo, hi).count()
+    }
+    fn bst_iter(&self) -> BSTIterator {
+        BSTIterator::new(self.clone())
+    }
+    fn height(&self) -> usize {
+        self.aggregate(|_| 1, |v, l, r| Some(v + l.max(r).unwrap_or(0)))
+            .unwrap_or(0)
+    }
+    fn node_count(&self) -> usize {
+        self.traverse(TraversalType::Preorder).count()
+    }
+    fn diameter(&self) -> usize {
+        let mut diameter = 0;
+        self.aggregate_t(
+            &mut diameter,
+            |_, _| 0,
+            |diameter: &mut usize, _, l: Option<usize>, r: Option<usize>| {
+                // the edges down to the deepest leaf, through each side
+                let (l, r) = (l.map_or(0, |l| l + 1), r.map_or(0, |r| r + 1));
+                *diameter = (*diameter).max(l + r);
+                Some(l.max(r))
+            },
+        );
+        diameter
     }
-    fn aggregate_t<R, T>(
+    fn max_path_sum(&self) -> Option<i64> {
+        let mut best = None;
+        self.aggregate_t(
+            &mut best,
+            |_, node| i64::from(node.get_val().unwrap()),
+            |best: &mut Option<i64>, val, l: Option<i64>, r: Option<i64>| {
+                // the best path going down from each child, if it helps
+                let (l, r) = (l.unwrap_or(0).max(0), r.unwrap_or(0).max(0));
+                *best = Some(best.map_or(val + l + r, |b| b.max(val + l + r)));
+                Some(val + l.max(r))
+            },
+        );
+        best
+    }
+    fn lca(
         &self,
-        result: &mut R,
-        val_func: fn(&mut R, Option<Rc<RefCell<TreeNode>>>) -> T,
-        aggr_func: fn(&mut R, T, Option<T>, Option<T>) -> Option<T>,
-    ) -> Option<T> {
-        if let Some(node) = self {
-            let n = node.borrow();
-            let mut lval = None;
-            let mut rval = None;
-            if let Some(left) = &n.left {
-                lval = Some(Rc::clone(left)).aggregate_t(result, val_func, aggr_func);
+        p: &Rc<RefCell<TreeNode>>,
+        q: &Rc<RefCell<TreeNode>>,
+    ) -> Option<Rc<RefCell<TreeNode>>> {
+        let (p, q) = (self.path_to(p)?, self.path_to(q)?);
+        p.into_iter()
+            .zip(q)
+            .take_while(|(a, b)| Rc::ptr_eq(a, b))
+            .last()
+            .map(|(a, _)| a)
+    }
+    fn lca_by_val(&self, p: i32, q: i32) -> Option<Rc<RefCell<TreeNode>>> {
+        let find = |val: i32| {
+            self.traverse(TraversalType::Preorder)
+                .find(|v| v.node.borrow().val == val)
+                .map(|v| v.node)
+        };
+        self.lca(&find(p)?, &find(q)?)
+    }
+    fn path_to(&self, target: &Rc<RefCell<TreeNode>>) -> Option<Vec<Rc<RefCell<TreeNode>>>> {
+        // the preorder visits the path to a node just before the node
+        let mut path: Vec<Rc<RefCell<TreeNode>>> = Vec::new();
+        for visit in self.traverse(TraversalType::Preorder) {
+            path.truncate(visit.depth);
+            path.push(visit.node);
+            if Rc::ptr_eq(path.last().unwrap(), target) {
+                return Some(path);
             }
-            if let Some(right) = &n.right {
-                rval = Some(Rc::clone(right)).aggregate_t(result, val_func, aggr_func);
+        }
+        None
+    }
+    fn root_to_leaf_paths(&self) -> Vec<Vec<i32>> {
+        let mut result = Vec::new();
+        let mut path = Vec::new();
+        for visit in self.traverse(TraversalType::Preorder) {
+            let n = visit.node.borrow();
+            path.truncate(visit.depth);
+            path.push(n.val);
+            if n.left.is_none() && n.right.is_none() {
+                result.push(path.clone());
             }
-            let val = val_func(result, Some(Rc::clone(&node)));
-            aggr_func(result, val, lval, rval)
-        } else {
-            None
         }
+        result
+    }
+    fn parent_map(&self) -> HashMap<*const RefCell<TreeNode>, Rc<RefCell<TreeNode>>> {
+        self.traverse(TraversalType::Preorder)
+            .filter_map(|v| Some((Rc::as_ptr(&v.node), v.parent?)))
+            .collect()
+    }
+    fn distance_k(&self, target: &Rc<RefCell<TreeNode>>, k: usize) -> Vec<i32> {
+        if self.path_to(target).is_none() {
+            return Vec::new();
+        }
+        let parents = self.parent_map();
+        let mut seen = HashSet::new();
+        seen.insert(Rc::as_ptr(target));
+        let mut row = vec![Rc::clone(target)];
+        for _ in 0..k {
+            let mut next_row = Vec::new();
+            for node in row {
+                let n = node.borrow();
+                let parent = parents.get(&Rc::as_ptr(&node)).cloned();
+                let neighbors = vec![n.left.clone(), n.right.clone(), parent];
+                for next in neighbors.into_iter().flatten() {
+                    if seen.insert(Rc::as_ptr(&next)) {
+                        next_row.push(next);
+                    }
+                }
+            }
+            row = next_row;
+        }
+        row.iter().map(|n| n.borrow().val).collect()
     }
+    fn compare(&self, expected: &Self) -> Result<(), String> {
+        let text = |node: &Self| node.get_val().map_or("null".to_string(), |v| v.to_string());
+        // the preorder visits the path to a pair just before the pair, like in `path_to`
+        let mut path: Vec<&str> = Vec::new();
+        let mut stack = Vec::new();
+        if self.is_some() || expected.is_some() {
+            stack.push((self.clone(), expected.clone(), 0, "root"));
+        }
+        while let Some((actual, expected, depth, side)) = stack.pop() {
+            path.truncate(depth);
+            path.push(side);
+            let (a, e) = match (&actual, &expected) {
+                (Some(a), Some(e)) => (a.borrow(), e.borrow()),
+                _ => {
+                    return Err(format!(
+                        "{}: expected {}, got {}",
+                        path.join("."),
+                        text(&expected),
+                        text(&actual)
+                    ))
+                }
+            };
+            if a.val != e.val {
+                return Err(format!(
+                    "{}: expected {}, got {}",
+                    path.join("."),
+                    e.val,
+                    a.val
+                ));
+            }
+            for (a, e, side) in [(&a.right, &e.right, "right"), (&a.left, &e.left, "left")] {
+                if a.is_some() || e.is_some() {
+                    stack.push((a.clone(), e.clone(), depth + 1, side));
+                }
+            }
+        }
+        Ok(())
+    }
+    fn is_subtree(&self, sub: &Self) -> bool {
+        let mut ids = SubtreeIds::new();
+        let target = ids.id(sub);
+        sub.is_none() || ids.number(self).iter().any(|&(_, id)| id == target)
+    }
+    fn is_mirror(&self, other: &Self) -> bool {
+        let mut stack = vec![(self.clone(), other.clone())];
+        while let Some(pair) = stack.pop() {
+            match pair {
+                (Some(a), Some(b)) => {
+                    let (a, b) = (a.borrow(), b.borrow());
+                    if a.val != b.val {
+                        return false;
+                    }
+                    stack.push((a.left.clone(), b.right.clone()));
+                    stack.push((a.right.clone(), b.left.clone()));
+                }
+                (None, None) => {}
+                _ => return false,
+            }
+        }
+        true
+    }
+    fn is_symmetric(&self) -> bool {
+        self.get_left().is_mirror(&self.get_right())
+    }
+    fn is_isomorphic(&self, other: &Self) -> bool {
+        let mut ids = SubtreeIds::with_flips();
+        ids.id(self) == ids.id(other)
+    }
+    fn duplicate_subtrees(&self) -> Vec<Rc<RefCell<TreeNode>>> {
+        let mut counts = HashMap::new();
+        let mut result = Vec::new();
+        for (node, id) in SubtreeIds::new().number(self) {
+            let count = counts.entry(id).or_insert(0);
+            *count += 1;
+            if *count == 2 {
+                result.push(node);
+            }
+        }
+        result
+    }
+}
+
+/// The values of a binary search tree in `[lo, hi]`, skipping the subtrees outside of it.
+fn bst_range(tree: &Option<Rc<RefCell<TreeNode>>>, lo: i32, hi: i32) -> impl Iterator<Item = i32> {
+    let mut stack: Vec<Rc<RefCell<TreeNode>>> = tree.iter().cloned().collect();
+    std::iter::from_fn(move || {
+        while let Some(node) = stack.pop() {
+            let n = node.borrow();
+            if n.val > lo {
+                stack.extend(n.left.clone());
+            }
+            if n.val < hi {
+                stack.extend(n.right.clone());
+            }
+            if lo <= n.val && n.val <= hi {
+                return Some(n.val);
+            }
+        }
+        None
+    })
 }
 
 impl TreeNode {
-    pub fn from_string(s: &str) -> Option<Rc<RefCell<TreeNode>>> {
-        let v: Vec<&str> = s.split(',').collect();
-        if v.len() == 0 {
-            None
-        } else {
-            let root = Rc::new(RefCell::new(TreeNode::new(v[0].parse().unwrap())));
-            let mut last_row = vec![Rc::clone(&root)];
-            let mut iter = v.iter().skip(1);
-            'outer: loop {
-                let mut new_row = Vec::new();
-                for parent in last_row.iter_mut() {
-                    let left = match iter.next() {
-                        Some(&"null") => None,
-                        Some(num) => {
-                            Some(Rc::from(RefCell::from(TreeNode::new(num.parse().unwrap()))))
-                        }
-                        _ => break 'outer,
-                    };
-                    let right = match iter.next() {
-                        Some(&"null") => None,
-                        Some(num) => {
-                            Some(Rc::from(RefCell::from(TreeNode::new(num.parse().unwrap()))))
-                        }
-                        _ => None,
-                    };
-                    Some(Rc::clone(parent)).set_sub_nodes(&left, &right);
-                    if left != None {
-                        new_row.push(left.unwrap());
+    /// Build a tree from its LeetCode level order form, e.g. `[1,null,2,3]`. The brackets are
+    /// optional, and `[]` is the empty tree.
+    pub fn from_string(s: &str) -> Result<Option<Rc<RefCell<TreeNode>>>, String> {
+        let s = s.trim();
+        let inner = s
+            .strip_prefix('[')
+            .and_then(|s| s.strip_suffix(']'))
+            .unwrap_or(s);
+        if inner.trim().is_empty() {
+            return Ok(None);
+        }
+        let mut values = Vec::new();
+        for item in inner.split(',') {
+            let item = item.trim();
+            values.push(match item {
+                "null" => None,
+                _ => Some(
+                    item.parse::<i32>()
+                        .map_err(|_| format!("invalid tree node `{}` in `{}`", item, s))?,
+                ),
+            });
+        }
+        let new_node = |v: Option<i32>| v.map(|v| Rc::new(RefCell::new(TreeNode::new(v))));
+
+        let mut values = values.into_iter();
+        let root = new_node(values.next().unwrap_or(None));
+        let mut last_row: Vec<Rc<RefCell<TreeNode>>> = root.iter().cloned().collect();
+        while !last_row.is_empty() {
+            let mut new_row = Vec::new();
+            for parent in last_row {
+                let left = new_node(values.next().unwrap_or(None));
+                let right = new_node(values.next().unwrap_or(None));
+                Some(parent).set_sub_nodes(&left, &right);
+                new_row.extend(left);
+                new_row.extend(right);
+            }
+            last_row = new_row;
+        }
+        if values.next().is_some() {
+            return Err(format!("`{}` has values after the last node", s));
+        }
+        Ok(root)
+    }
+
+    /// Rebuild a tree from its preorder and inorder values, which must be distinct.
+    ///
+    /// # Example
+    /// ```
+    /// use cauly_rust_leetcode_utils::binary_tree::*;
+    /// let tree = TreeNode::from_preorder_inorder(&[3, 9, 20, 15, 7], &[9, 3, 15, 20, 7]).unwrap();
+    /// assert_eq!("[3,9,20,null,null,15,7]", tree.to_leetcode_string());
+    /// assert!(TreeNode::from_preorder_inorder(&[1, 2], &[1, 3]).is_err());
+    /// ```
+    pub fn from_preorder_inorder(
+        preorder: &[i32],
+        inorder: &[i32],
+    ) -> Result<Option<Rc<RefCell<TreeNode>>>, String> {
+        check_traversals(preorder, inorder)?;
+        let (root, rest) = match preorder.split_first() {
+            Some((&first, rest)) => (tree_node(first), rest),
+            None => return Ok(None),
+        };
+        let mut stack = vec![Rc::clone(&root)];
+        let mut next = inorder.iter().peekable();
+        for &val in rest {
+            let node = tree_node(val);
+            let top = Rc::clone(stack.last().unwrap());
+            if next.peek() != Some(&&top.borrow().val) {
+                top.borrow_mut().left = Some(Rc::clone(&node));
+            } else {
+                // the new node is the right child of the last node whose inorder turn came
+                let mut parent = top;
+                while let Some(top) = stack.last() {
+                    if next.peek() != Some(&&top.borrow().val) {
+                        break;
+                    }
+                    parent = stack.pop().unwrap();
+                    next.next();
+                }
+                parent.borrow_mut().right = Some(Rc::clone(&node));
+            }
+            stack.push(node);
+        }
+        let root = Some(root);
+        check_order(&root, TraversalType::Inorder, inorder, "inorder")?;
+        Ok(root)
+    }
+
+    /// Rebuild a tree from its inorder and postorder values, which must be distinct.
+    ///
+    /// # Example
+    /// ```
+    /// use cauly_rust_leetcode_utils::binary_tree::*;
+    /// let tree = TreeNode::from_inorder_postorder(&[9, 3, 15, 20, 7], &[9, 15, 7, 20, 3]).unwrap();
+    /// assert_eq!("[3,9,20,null,null,15,7]", tree.to_leetcode_string());
+    /// ```
+    pub fn from_inorder_postorder(
+        inorder: &[i32],
+        postorder: &[i32],
+    ) -> Result<Option<Rc<RefCell<TreeNode>>>, String> {
+        check_traversals(postorder, inorder)?;
+        let (root, rest) = match postorder.split_last() {
+            Some((&last, rest)) => (tree_node(last), rest),
+            None => return Ok(None),
+        };
+        // the same as from_preorder_inorder, on the reversed sequences and with the sides swapped
+        let mut stack = vec![Rc::clone(&root)];
+        let mut next = inorder.iter().rev().peekable();
+        for &val in rest.iter().rev() {
+            let node = tree_node(val);
+            let top = Rc::clone(stack.last().unwrap());
+            if next.peek() != Some(&&top.borrow().val) {
+                top.borrow_mut().right = Some(Rc::clone(&node));
+            } else {
+                let mut parent = top;
+                while let Some(top) = stack.last() {
+                    if next.peek() != Some(&&top.borrow().val) {
+                        break;
                     }
-                    if right != None {
-                        new_row.push(right.unwrap());
+                    parent = stack.pop().unwrap();
+                    next.next();
+                }
+                parent.borrow_mut().left = Some(Rc::clone(&node));
+            }
+            stack.push(node);
+        }
+        let root = Some(root);
+        check_order(&root, TraversalType::Inorder, inorder, "inorder")?;
+        Ok(root)
+    }
+
+    /// Rebuild a tree from its preorder and postorder values, which must be distinct. Only full
+    /// binary trees are described uniquely by them; a node with a single child gets it on the
+    /// left.
+    ///
+    /// # Example
+    /// ```
+    /// use cauly_rust_leetcode_utils::binary_tree::*;
+    /// let tree = TreeNode::from_preorder_postorder(&[1, 2, 4, 5, 3, 6, 7], &[4, 5, 2, 6, 7, 3, 1]).unwrap();
+    /// assert_eq!("[1,2,3,4,5,6,7]", tree.to_leetcode_string());
+    /// ```
+    pub fn from_preorder_postorder(
+        preorder: &[i32],
+        postorder: &[i32],
+    ) -> Result<Option<Rc<RefCell<TreeNode>>>, String> {
+        check_traversals(preorder, postorder)?;
+        let mut root = None;
+        let mut stack: Vec<Rc<RefCell<TreeNode>>> = Vec::new();
+        let mut next = postorder.iter().peekable();
+        for &val in preorder {
+            let node = tree_node(val);
+            match stack.last() {
+                Some(top) => {
+                    let mut top = top.borrow_mut();
+                    if top.left.is_none() {
+                        top.left = Some(Rc::clone(&node));
+                    } else if top.right.is_none() {
+                        top.right = Some(Rc::clone(&node));
+                    } else {
+                        return Err("`preorder` and `postorder` don't match".to_string());
                     }
                 }
-                last_row = new_row;
-                if last_row.len() == 0 {
+                None if root.is_none() => root = Some(Rc::clone(&node)),
+                None => return Err("`preorder` and `postorder` don't match".to_string()),
+            }
+            stack.push(node);
+            // a node is complete once its postorder turn came
+            while let Some(top) = stack.last() {
+                if next.peek() != Some(&&top.borrow().val) {
                     break;
                 }
+                stack.pop();
+                next.next();
+            }
+        }
+        check_order(&root, TraversalType::Postorder, postorder, "postorder")?;
+        Ok(root)
+    }
+
+    /// A height-balanced binary search tree of ascending `values`. The root of each subtree is
+    /// its middle value, or the upper middle one for an even number of values.
+    ///
+    /// # Example
+    /// ```
+    /// use cauly_rust_leetcode_utils::binary_tree::*;
+    /// let tree = TreeNode::from_sorted(&[-10, -3, 0, 5, 9]).unwrap();
+    /// assert_eq!("[0,-3,9,-10,null,5]", tree.to_leetcode_string());
+    /// assert!(TreeNode::from_sorted(&[1, 1]).is_err());
+    /// ```
+    pub fn from_sorted(values: &[i32]) -> Result<Option<Rc<RefCell<TreeNode>>>, String> {
+        if let Some(w) = values.windows(2).find(|w| w[0] >= w[1]) {
+            return Err(format!(
+                "values are not strictly ascending: {} before {}",
+                w[0], w[1]
+            ));
+        }
+        fn build(values: &[i32]) -> Option<Rc<RefCell<TreeNode>>> {
+            if values.is_empty() {
+                return None;
+            }
+            let middle = values.len() / 2;
+            let node = tree_node(values[middle]);
+            {
+                let mut n = node.borrow_mut();
+                n.left = build(&values[..middle]);
+                n.right = build(&values[middle + 1..]);
+            }
+            Some(node)
+        }
+        Ok(build(values))
+    }
+
+    /// Rebuild a binary search tree from its preorder values, which must be distinct.
+    ///
+    /// # Example
+    /// ```
+    /// use cauly_rust_leetcode_utils::binary_tree::*;
+    /// let tree = TreeNode::from_bst_preorder(&[8, 5, 1, 7, 10, 12]).unwrap();
+    /// assert_eq!("[8,5,10,1,7,null,12]", tree.to_leetcode_string());
+    /// assert!(TreeNode::from_bst_preorder(&[3, 1, 4, 2]).is_err());
+    /// ```
+    pub fn from_bst_preorder(preorder: &[i32]) -> Result<Option<Rc<RefCell<TreeNode>>>, String> {
+        check_distinct(preorder)?;
+        let (root, rest) = match preorder.split_first() {
+            Some((&first, rest)) => (tree_node(first), rest),
+            None => return Ok(None),
+        };
+        let mut stack = vec![Rc::clone(&root)];
+        // every later value has to be greater than the nodes whose right subtree was entered
+        let mut lower = i64::MIN;
+        for &val in rest {
+            if i64::from(val) < lower {
+                return Err(format!("{} can't come after {} in a preorder", val, lower));
+            }
+            let node = tree_node(val);
+            let top = Rc::clone(stack.last().unwrap());
+            if val < top.borrow().val {
+                top.borrow_mut().left = Some(Rc::clone(&node));
+            } else {
+                let mut parent = top;
+                while let Some(top) = stack.last() {
+                    if top.borrow().val > val {
+                        break;
+                    }
+                    parent = stack.pop().unwrap();
+                }
+                lower = i64::from(parent.borrow().val);
+                parent.borrow_mut().right = Some(Rc::clone(&node));
+            }
+            stack.push(node);
+        }
+        Ok(Some(root))
+    }
+}
+
+fn tree_node(val: i32) -> Rc<RefCell<TreeNode>> {
+    Rc::new(RefCell::new(TreeNode::new(val)))
+}
+
+fn check_distinct(values: &[i32]) -> Result<(), String> {
+    let mut seen = HashSet::new();
+    match values.iter().find(|v| !seen.insert(**v)) {
+        Some(v) => Err(format!("{} appears more than once", v)),
+        None => Ok(()),
+    }
+}
+
+/// Two traversals of the same tree have the same distinct values.
+fn check_traversals(a: &[i32], b: &[i32]) -> Result<(), String> {
+    if a.len() != b.len() {
+        return Err(format!(
+            "the traversals have {} and {} values",
+            a.len(),
+            b.len()
+        ));
+    }
+    check_distinct(a)?;
+    let (mut a, mut b) = (a.to_vec(), b.to_vec());
+    a.sort_unstable();
+    b.sort_unstable();
+    if a != b {
+        return Err("the traversals have different values".to_string());
+    }
+    Ok(())
+}
+
+/// The traversal `t` of the built tree is `expected`, i.e. the input sequences were consistent.
+fn check_order(
+    tree: &Option<Rc<RefCell<TreeNode>>>,
+    t: TraversalType,
+    expected: &[i32],
+    name: &str,
+) -> Result<(), String> {
+    if tree
+        .traverse(t)
+        .map(|v| v.node.borrow().val)
+        .eq(expected.iter().cloned())
+    {
+        Ok(())
+    } else {
+        Err(format!("`{}` doesn't match the other traversal", name))
+    }
+}
+// #endregion
+
+// #region Tree drawing
+/// `─ ┌ ┐ ┴ ┘ └ │`, or their ASCII replacements.
+fn draw_chars(ascii: bool) -> [char; 7] {
+    if ascii {
+        ['-', '+', '+', '+', '+', '+', '|']
+    } else {
+        ['─', '┌', '┐', '┴', '┘', '└', '│']
+    }
+}
+
+fn node_label(node: &Rc<RefCell<TreeNode>>, options: &DrawOptions) -> String {
+    let val = node.borrow().val;
+    if options.marked.iter().any(|m| Rc::ptr_eq(m, node)) {
+        format!("[{}]", val)
+    } else {
+        val.to_string()
+    }
+}
+
+/// The lines of a subtree drawn top-down, all of the same width, and the column of its root,
+/// from the label of the root and the drawings of its subtrees.
+fn draw_top_down(
+    label: Vec<char>,
+    left: Option<(Vec<Vec<char>>, usize)>,
+    right: Option<(Vec<Vec<char>>, usize)>,
+    options: &DrawOptions,
+) -> (Vec<Vec<char>>, usize) {
+    let [horizontal, left_end, right_end, both, up_left, up_right, _] = draw_chars(options.ascii);
+    let block_width = |block: &Option<(Vec<Vec<char>>, usize)>| match block {
+        Some((lines, _)) => lines[0].len(),
+        None => 0,
+    };
+    let (left_width, right_width) = (block_width(&left), block_width(&right));
+    let width = left_width + label.len() + right_width;
+    let middle = left_width + label.len() / 2;
+
+    let mut first = vec![' '; left_width];
+    first.extend(&label);
+    first.resize(width, ' ');
+    let mut lines = vec![first];
+    if left.is_none() && right.is_none() {
+        return (lines, middle);
+    }
+    let mut connector = vec![' '; width];
+    let start = left.as_ref().map_or(middle, |(_, m)| *m);
+    let end = right
+        .as_ref()
+        .map_or(middle, |(_, m)| left_width + label.len() + m);
+    for c in &mut connector[start..=end] {
+        *c = horizontal;
+    }
+    connector[start] = left_end;
+    connector[end] = right_end;
+    connector[middle] = match (&left, &right) {
+        (Some(_), Some(_)) => both,
+        (Some(_), None) => up_left,
+        _ => up_right,
+    };
+    lines.push(connector);
+    let height = |block: &Option<(Vec<Vec<char>>, usize)>| block.as_ref().map_or(0, |b| b.0.len());
+    for i in 0..height(&left).max(height(&right)) {
+        let mut line = Vec::with_capacity(width);
+        for (block, block_width) in [
+            (&left, left_width),
+            (&None, label.len()),
+            (&right, right_width),
+        ] {
+            match block.as_ref().and_then(|(lines, _)| lines.get(i)) {
+                Some(l) => line.extend(l),
+                None => line.resize(line.len() + block_width, ' '),
             }
+        }
+        lines.push(line);
+    }
+    (lines, middle)
+}
 
-            Some(root)
+/// Draw a tree root on the left, one line per node, with the right subtree of each node above
+/// it and the left one below.
+fn draw_sideways(root: &Rc<RefCell<TreeNode>>, options: &DrawOptions) -> String {
+    let [horizontal, left_end, _, _, _, up_right, vertical] = draw_chars(options.ascii);
+    let mut result = String::new();
+    // the nodes to draw, with their prefix, which child of their parent they are, and whether
+    // their subtrees were already pushed
+    let mut stack = vec![(Rc::clone(root), String::new(), None, false)];
+    while let Some((node, prefix, is_left, expanded)) = stack.pop() {
+        if expanded {
+            result.push_str(&prefix);
+            if let Some(left) = is_left {
+                let corner = if left { up_right } else { left_end };
+                result.extend([corner, horizontal, horizontal, ' ']);
+            }
+            result.push_str(&node_label(&node, options));
+            result.push('\n');
+            continue;
+        }
+        let child_prefix = |above: bool| {
+            // the edge to the parent passes by the children on the side of the parent
+            if is_left == Some(above) {
+                format!("{}{}   ", prefix, vertical)
+            } else if is_left.is_some() {
+                format!("{}    ", prefix)
+            } else {
+                prefix.clone()
+            }
+        };
+        let n = node.borrow();
+        if let Some(left) = &n.left {
+            stack.push((Rc::clone(left), child_prefix(false), Some(true), false));
         }
+        let right = n
+            .right
+            .as_ref()
+            .map(|r| (Rc::clone(r), child_prefix(true), Some(false), false));
+        stack.push((Rc::clone(&node), prefix.clone(), is_left, true));
+        stack.extend(right);
     }
+    result
 }
 // #endregion
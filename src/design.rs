@@ -0,0 +1,239 @@
+use crate::leetcode_io::{parse, parse_value, LeetCodeValue, Value};
+
+// #region design
+/// Run the calls of a design problem and compare their outputs, e.g. the operations
+/// `["LRUCache","put","get"]` with the arguments `[[2],[1,1],[1]]` and the expected outputs
+/// `[null,null,1]`. Every call goes to `dispatch` with the object, the operation and its
+/// arguments. The first call is the constructor, which should set the object and return
+/// [`Value::Null`]. Fails with the first step whose output differs from the expected one.
+///
+/// [`design_dispatch!`](crate::design_dispatch) generates `dispatch` from the methods of a struct.
+///
+/// # Example
+/// ```
+/// use cauly_rust_leetcode_utils::design::*;
+/// use cauly_rust_leetcode_utils::leetcode_io::*;
+/// let dispatch = |sum: &mut Option<i64>, operation: &str, args: &[Value]| match operation {
+///     "Sum" => {
+///         *sum = Some(0);
+///         Ok(Value::Null)
+///     }
+///     "add" => {
+///         let sum = sum.as_mut().unwrap();
+///         *sum += i64::from_value(&args[0])?;
+///         Ok(sum.to_value())
+///     }
+///     _ => Err(format!("unknown operation `{}`", operation)),
+/// };
+/// assert_eq!(Ok(()), run_design(r#"["Sum","add","add"]"#, "[[],[1],[2]]", "[null,1,3]", dispatch));
+/// assert_eq!(
+///     Err("step 2 `add(2)`: expected 4, got 3".to_string()),
+///     run_design(r#"["Sum","add","add"]"#, "[[],[1],[2]]", "[null,1,4]", dispatch)
+/// );
+/// ```
+pub fn run_design<T, F>(
+    operations: &str,
+    arguments: &str,
+    expected: &str,
+    mut dispatch: F,
+) -> Result<(), String>
+where
+    F: FnMut(&mut Option<T>, &str, &[Value]) -> Result<Value, String>,
+{
+    let operations: Vec<String> = parse(operations)?;
+    let arguments: Vec<Vec<Value>> = match parse_value(arguments)? {
+        Value::List(items) => items
+            .into_iter()
+            .map(|item| match item {
+                Value::List(args) => Ok(args),
+                other => Err(format!("expected a list of arguments, found `{}`", other)),
+            })
+            .collect::<Result<_, _>>()?,
+        other => return Err(format!("expected a list of arguments, found `{}`", other)),
+    };
+    let expected: Vec<Value> = match parse_value(expected)? {
+        Value::List(items) => items,
+        other => return Err(format!("expected a list of outputs, found `{}`", other)),
+    };
+    if operations.len() != arguments.len() || operations.len() != expected.len() {
+        return Err(format!(
+            "{} operations, {} argument lists and {} expected outputs",
+            operations.len(),
+            arguments.len(),
+            expected.len()
+        ));
+    }
+
+    let mut object = None;
+    for (step, ((operation, args), expected)) in
+        operations.iter().zip(&arguments).zip(&expected).enumerate()
+    {
+        let args_text: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        let call = format!("{}({})", operation, args_text.join(","));
+        if step > 0 && object.is_none() {
+            return Err(format!(
+                "step {} `{}`: no object was constructed",
+                step, call
+            ));
+        }
+        let actual = dispatch(&mut object, operation, args)
+            .map_err(|e| format!("step {} `{}`: {}", step, call, e))?;
+        if !same_output(&actual, expected) {
+            return Err(format!(
+                "step {} `{}`: expected {}, got {}",
+                step, call, expected, actual
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// LeetCode accepts floats within 1e-5 of the expected output.
+fn same_output(actual: &Value, expected: &Value) -> bool {
+    let number = |v: &Value| match v {
+        Value::Int(i) => Some(*i as f64),
+        Value::Float(x) => Some(*x),
+        _ => None,
+    };
+    match (actual, expected) {
+        (Value::Float(_), _) | (_, Value::Float(_)) => match (number(actual), number(expected)) {
+            (Some(a), Some(b)) => (a - b).abs() <= 1e-5,
+            _ => false,
+        },
+        (Value::List(a), Value::List(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| same_output(a, b))
+        }
+        _ => actual == expected,
+    }
+}
+
+/// The arguments of a call, converted one at a time to the types of the parameters.
+pub struct DesignArgs<'a> {
+    operation: &'a str,
+    values: &'a [Value],
+    next: usize,
+}
+
+impl<'a> DesignArgs<'a> {
+    pub fn new(operation: &'a str, values: &'a [Value]) -> Self {
+        DesignArgs {
+            operation,
+            values,
+            next: 0,
+        }
+    }
+
+    /// The next argument.
+    pub fn arg<T: LeetCodeValue>(&mut self) -> Result<T, String> {
+        let value = self.values.get(self.next).ok_or_else(|| {
+            format!(
+                "`{}` takes more than {} arguments",
+                self.operation,
+                self.values.len()
+            )
+        })?;
+        self.next += 1;
+        T::from_value(value).map_err(|e| format!("argument {}: {}", self.next, e))
+    }
+
+    /// Fails if some arguments were not used.
+    pub fn finish(&self) -> Result<(), String> {
+        if self.next == self.values.len() {
+            Ok(())
+        } else {
+            Err(format!(
+                "`{}` takes {} arguments, found {}",
+                self.operation,
+                self.next,
+                self.values.len()
+            ))
+        }
+    }
+}
+
+/// The name of the rust method for a LeetCode operation, e.g. `add_num` for `addNum`.
+///
+/// # Example
+/// ```
+/// use cauly_rust_leetcode_utils::design::*;
+/// assert_eq!("add_num", snake_case("addNum"));
+/// assert_eq!("get", snake_case("get"));
+/// ```
+pub fn snake_case(name: &str) -> String {
+    let mut result = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i > 0 {
+                result.push('_');
+            }
+            result.push(c.to_ascii_lowercase());
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// A dispatch function for [`run_design`] that calls the constructor and methods of a struct.
+/// Only the number of parameters is given, their types come from the methods. Operations in
+/// camel case call the snake case methods, and the returned values are converted with
+/// [`LeetCodeValue`](crate::leetcode_io::LeetCodeValue).
+///
+/// # Example
+/// ```
+/// use cauly_rust_leetcode_utils::design::*;
+/// use cauly_rust_leetcode_utils::design_dispatch;
+/// struct MinStack(Vec<i32>);
+/// impl MinStack {
+///     fn new() -> Self {
+///         MinStack(Vec::new())
+///     }
+///     fn push(&mut self, val: i32) {
+///         self.0.push(val);
+///     }
+///     fn pop(&mut self) {
+///         self.0.pop();
+///     }
+///     fn get_min(&self) -> i32 {
+///         *self.0.iter().min().unwrap()
+///     }
+/// }
+/// assert_eq!(
+///     Ok(()),
+///     run_design(
+///         r#"["MinStack","push","push","getMin","pop","getMin"]"#,
+///         "[[],[-2],[0],[],[],[]]",
+///         "[null,null,null,-2,null,-2]",
+///         design_dispatch!(MinStack::new(), push(val), pop(), get_min()),
+///     )
+/// );
+/// ```
+#[macro_export]
+macro_rules! design_dispatch {
+    ($type:ident :: $new:ident ( $($new_arg:ident),* ) $(, $method:ident ( $($arg:ident),* ))* $(,)?) => {
+        |object: &mut Option<$type>,
+         operation: &str,
+         values: &[$crate::leetcode_io::Value]|
+         -> Result<$crate::leetcode_io::Value, String> {
+            let mut _args = $crate::design::DesignArgs::new(operation, values);
+            if operation == stringify!($type) {
+                $(let $new_arg = _args.arg()?;)*
+                _args.finish()?;
+                *object = Some($type::$new($($new_arg),*));
+                return Ok($crate::leetcode_io::Value::Null);
+            }
+            let _object = object.as_mut().ok_or("no object was constructed")?;
+            let _method = $crate::design::snake_case(operation);
+            $(
+                if _method == stringify!($method) {
+                    $(let $arg = _args.arg()?;)*
+                    _args.finish()?;
+                    let result = _object.$method($($arg),*);
+                    return Ok($crate::leetcode_io::LeetCodeValue::to_value(&result));
+                }
+            )*
+            Err(format!("unknown operation `{}`", operation))
+        }
+    };
+}
+// #endregion
@@ -0,0 +1,322 @@
+// #region leetcode_io
+use std::convert::TryFrom;
+use std::fmt;
+
+/// A value written in LeetCode's literal syntax, e.g. `[[1,2],[3]]`, `["a","b"]`, `true`,
+/// `null` or `2.50000`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Str(String),
+    List(Vec<Value>),
+}
+
+impl Value {
+    fn type_name(&self) -> &'static str {
+        match self {
+            Value::Null => "null",
+            Value::Bool(_) => "a bool",
+            Value::Int(_) => "an integer",
+            Value::Float(_) => "a number",
+            Value::Str(_) => "a string",
+            Value::List(_) => "a list",
+        }
+    }
+
+    fn expected<T>(&self, what: &str) -> Result<T, String> {
+        Err(format!(
+            "expected {}, found {} `{}`",
+            what,
+            self.type_name(),
+            self
+        ))
+    }
+}
+
+/// Formats the value the way LeetCode prints it: no spaces, and 5 decimals for floats.
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Null => write!(f, "null"),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Int(i) => write!(f, "{}", i),
+            Value::Float(x) => write!(f, "{:.5}", x),
+            Value::Str(s) => {
+                write!(f, "\"")?;
+                for c in s.chars() {
+                    match c {
+                        '"' => write!(f, "\\\"")?,
+                        '\\' => write!(f, "\\\\")?,
+                        '\n' => write!(f, "\\n")?,
+                        '\t' => write!(f, "\\t")?,
+                        _ => write!(f, "{}", c)?,
+                    }
+                }
+                write!(f, "\"")
+            }
+            Value::List(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+/// Parse a LeetCode literal. Whitespace between tokens is allowed.
+pub fn parse_value(s: &str) -> Result<Value, String> {
+    let chars: Vec<char> = s.chars().collect();
+    let mut pos = 0;
+    let value = parse_at(&chars, &mut pos)?;
+    skip_whitespace(&chars, &mut pos);
+    if pos < chars.len() {
+        return Err(format!(
+            "unexpected `{}` after the value in `{}`",
+            chars[pos], s
+        ));
+    }
+    Ok(value)
+}
+
+fn skip_whitespace(chars: &[char], pos: &mut usize) {
+    while *pos < chars.len() && chars[*pos].is_whitespace() {
+        *pos += 1;
+    }
+}
+
+fn parse_at(chars: &[char], pos: &mut usize) -> Result<Value, String> {
+    skip_whitespace(chars, pos);
+    match chars.get(*pos) {
+        None => Err("unexpected end of input".to_string()),
+        Some('[') => {
+            *pos += 1;
+            let mut items = Vec::new();
+            skip_whitespace(chars, pos);
+            if chars.get(*pos) == Some(&']') {
+                *pos += 1;
+                return Ok(Value::List(items));
+            }
+            loop {
+                items.push(parse_at(chars, pos)?);
+                skip_whitespace(chars, pos);
+                match chars.get(*pos) {
+                    Some(',') => *pos += 1,
+                    Some(']') => {
+                        *pos += 1;
+                        return Ok(Value::List(items));
+                    }
+                    Some(c) => return Err(format!("expected `,` or `]`, found `{}`", c)),
+                    None => return Err("missing `]`".to_string()),
+                }
+            }
+        }
+        Some('"') => {
+            *pos += 1;
+            let mut s = String::new();
+            loop {
+                let c = *chars.get(*pos).ok_or("missing closing `\"`")?;
+                *pos += 1;
+                match c {
+                    '"' => return Ok(Value::Str(s)),
+                    '\\' => {
+                        let e = *chars.get(*pos).ok_or("missing closing `\"`")?;
+                        *pos += 1;
+                        match e {
+                            'n' => s.push('\n'),
+                            't' => s.push('\t'),
+                            'r' => s.push('\r'),
+                            'u' => {
+                                let hex: String = chars.iter().skip(*pos).take(4).collect();
+                                let code = u32::from_str_radix(&hex, 16)
+                                    .ok()
+                                    .and_then(char::from_u32)
+                                    .ok_or_else(|| format!("invalid escape `\\u{}`", hex))?;
+                                s.push(code);
+                                *pos += 4;
+                            }
+                            _ => s.push(e),
+                        }
+                    }
+                    _ => s.push(c),
+                }
+            }
+        }
+        Some(_) => {
+            let start = *pos;
+            while *pos < chars.len() && !matches!(chars[*pos], ',' | ']' | '[' | '"') {
+                *pos += 1;
+            }
+            let word: String = chars[start..*pos].iter().collect();
+            let word = word.trim();
+            match word {
+                "null" => Ok(Value::Null),
+                "true" => Ok(Value::Bool(true)),
+                "false" => Ok(Value::Bool(false)),
+                _ => {
+                    if let Ok(i) = word.parse::<i64>() {
+                        Ok(Value::Int(i))
+                    } else if let Ok(x) = word.parse::<f64>() {
+                        Ok(Value::Float(x))
+                    } else {
+                        Err(format!("unexpected `{}`", word))
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Types that can be read from and written as LeetCode literals.
+pub trait LeetCodeValue: Sized {
+    fn from_value(value: &Value) -> Result<Self, String>;
+    fn to_value(&self) -> Value;
+}
+
+/// Parse a LeetCode literal into a rust value, e.g. `[[1,2],[3]]` into `Vec<Vec<i32>>`.
+pub fn parse<T: LeetCodeValue>(s: &str) -> Result<T, String> {
+    T::from_value(&parse_value(s)?)
+}
+
+/// Format a rust value the way LeetCode prints it.
+pub fn format<T: LeetCodeValue>(value: &T) -> String {
+    value.to_value().to_string()
+}
+
+impl LeetCodeValue for () {
+    fn from_value(value: &Value) -> Result<Self, String> {
+        match value {
+            Value::Null => Ok(()),
+            v => v.expected("null"),
+        }
+    }
+    fn to_value(&self) -> Value {
+        Value::Null
+    }
+}
+
+impl LeetCodeValue for i32 {
+    fn from_value(value: &Value) -> Result<Self, String> {
+        match value {
+            Value::Int(i) => {
+                i32::try_from(*i).map_err(|_| format!("{} is out of range for i32", i))
+            }
+            v => v.expected("an integer"),
+        }
+    }
+    fn to_value(&self) -> Value {
+        Value::Int(*self as i64)
+    }
+}
+
+impl LeetCodeValue for i64 {
+    fn from_value(value: &Value) -> Result<Self, String> {
+        match value {
+            Value::Int(i) => Ok(*i),
+            v => v.expected("an integer"),
+        }
+    }
+    fn to_value(&self) -> Value {
+        Value::Int(*self)
+    }
+}
+
+impl LeetCodeValue for usize {
+    fn from_value(value: &Value) -> Result<Self, String> {
+        match value {
+            Value::Int(i) => {
+                usize::try_from(*i).map_err(|_| format!("{} is out of range for usize", i))
+            }
+            v => v.expected("an integer"),
+        }
+    }
+    fn to_value(&self) -> Value {
+        Value::Int(*self as i64)
+    }
+}
+
+impl LeetCodeValue for f64 {
+    fn from_value(value: &Value) -> Result<Self, String> {
+        match value {
+            Value::Float(x) => Ok(*x),
+            Value::Int(i) => Ok(*i as f64),
+            v => v.expected("a number"),
+        }
+    }
+    fn to_value(&self) -> Value {
+        Value::Float(*self)
+    }
+}
+
+impl LeetCodeValue for bool {
+    fn from_value(value: &Value) -> Result<Self, String> {
+        match value {
+            Value::Bool(b) => Ok(*b),
+            v => v.expected("a bool"),
+        }
+    }
+    fn to_value(&self) -> Value {
+        Value::Bool(*self)
+    }
+}
+
+impl LeetCodeValue for String {
+    fn from_value(value: &Value) -> Result<Self, String> {
+        match value {
+            Value::Str(s) => Ok(s.clone()),
+            v => v.expected("a string"),
+        }
+    }
+    fn to_value(&self) -> Value {
+        Value::Str(self.clone())
+    }
+}
+
+/// Characters are written as strings of length 1, e.g. the cells of `[["1","0"]]`.
+impl LeetCodeValue for char {
+    fn from_value(value: &Value) -> Result<Self, String> {
+        match value {
+            Value::Str(s) if s.chars().count() == 1 => Ok(s.chars().next().unwrap()),
+            v => v.expected("a string of one character"),
+        }
+    }
+    fn to_value(&self) -> Value {
+        Value::Str(self.to_string())
+    }
+}
+
+impl<T: LeetCodeValue> LeetCodeValue for Vec<T> {
+    fn from_value(value: &Value) -> Result<Self, String> {
+        match value {
+            Value::List(items) => items.iter().map(T::from_value).collect(),
+            v => v.expected("a list"),
+        }
+    }
+    fn to_value(&self) -> Value {
+        Value::List(self.iter().map(|x| x.to_value()).collect())
+    }
+}
+
+/// `null` is None.
+impl<T: LeetCodeValue> LeetCodeValue for Option<T> {
+    fn from_value(value: &Value) -> Result<Self, String> {
+        match value {
+            Value::Null => Ok(None),
+            v => T::from_value(v).map(Some),
+        }
+    }
+    fn to_value(&self) -> Value {
+        match self {
+            Some(x) => x.to_value(),
+            None => Value::Null,
+        }
+    }
+}
+// #endregion
@@ -1,38 +1,158 @@
+pub mod arena_tree;
 pub mod binary_search;
 pub mod binary_tree;
+pub mod design;
 pub mod dp;
 pub mod graph;
 pub mod grid;
+pub mod leetcode_io;
+pub mod linked_list;
+pub mod random;
+pub mod rlfmt;
 pub mod segment_tree;
 pub mod union_find;
 
 pub const VERSION: &'static str = env!("CARGO_PKG_VERSION");
-pub const BINARY_TREE_SRC: &'static str = include_str!("binary_tree.rs");
-pub const GRID_SRC: &'static str = include_str!("grid.rs");
-pub const UNION_FIND_SRC: &'static str = include_str!("union_find.rs");
-pub const BINARY_SEARCH_SRC: &'static str = include_str!("binary_search.rs");
-pub const SEGMENT_TREE_SRC: &'static str = include_str!("segment_tree.rs");
-pub const DP_SRC: &'static str = include_str!("dp.rs");
-pub const GRAPH_SRC: &'static str = include_str!("graph.rs");
 
-pub fn get_module_source(str: String) -> &'static str {
-    if str.starts_with("binary_tree") {
-        BINARY_TREE_SRC
-    } else if str.starts_with("grid") {
-        GRID_SRC
-    } else if str.starts_with("union_find") {
-        UNION_FIND_SRC
-    } else if str.starts_with("binary_search") {
-        BINARY_SEARCH_SRC
-    } else if str.starts_with("segment_tree") {
-        SEGMENT_TREE_SRC
-    } else if str.starts_with("dp") {
-        DP_SRC
-    } else if str.starts_with("graph") {
-        GRAPH_SRC
-    } else if str.starts_with("define_dp") {
-        ""
-    } else {
-        panic!("unknown module for cauly_rust_leetcode_util");
-    }
+/// A module of this library, as rlfmt inlines it into submissions.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Module {
+    pub name: &'static str,
+    pub source: &'static str,
+    /// The other modules it uses through `crate::` paths.
+    pub deps: &'static [&'static str],
+    /// The public items it defines. `#[macro_export]` macros end with `!`, they are exported
+    /// at the root of the crate instead of the module.
+    pub items: &'static [&'static str],
+}
+
+/// All modules that can be inlined, in the order they are listed by `rlfmt --list`.
+pub static MODULES: &[Module] = &[
+    Module {
+        name: "binary_tree",
+        source: include_str!("binary_tree.rs"),
+        deps: &[],
+        items: &[
+            "TreeNode",
+            "TraversalType",
+            "TreeLayout",
+            "DrawOptions",
+            "TreeVisit",
+            "Traversal",
+            "Levels",
+            "BSTIterator",
+            "SubtreeIds",
+            "RefTreeNode",
+        ],
+    },
+    Module {
+        name: "arena_tree",
+        source: include_str!("arena_tree.rs"),
+        deps: &["binary_tree"],
+        items: &["ArenaTree", "ArenaTraversal"],
+    },
+    Module {
+        name: "random",
+        source: include_str!("random.rs"),
+        deps: &["binary_tree"],
+        items: &["Skew", "Rng"],
+    },
+    Module {
+        name: "grid",
+        source: include_str!("grid.rs"),
+        deps: &[],
+        items: &["Grid", "GridItem"],
+    },
+    Module {
+        name: "union_find",
+        source: include_str!("union_find.rs"),
+        deps: &[],
+        items: &["UnionFind4Usize", "UnionFind"],
+    },
+    Module {
+        name: "binary_search",
+        source: include_str!("binary_search.rs"),
+        deps: &[],
+        items: &["BinarySearch", "ReadonlyBinarySearch", "IBinarySearch"],
+    },
+    Module {
+        name: "segment_tree",
+        source: include_str!("segment_tree.rs"),
+        deps: &[],
+        items: &["SegmentTree"],
+    },
+    Module {
+        name: "dp",
+        source: include_str!("dp.rs"),
+        deps: &[],
+        items: &["DP", "DPIndexer", "define_dp!"],
+    },
+    Module {
+        name: "leetcode_io",
+        source: include_str!("leetcode_io.rs"),
+        deps: &[],
+        items: &["Value", "parse_value", "LeetCodeValue", "parse", "format"],
+    },
+    Module {
+        name: "linked_list",
+        source: include_str!("linked_list.rs"),
+        deps: &["leetcode_io"],
+        items: &["ListNode", "BoxListNode", "ListValues", "ListNodes"],
+    },
+    Module {
+        name: "design",
+        source: include_str!("design.rs"),
+        deps: &["leetcode_io"],
+        items: &["run_design", "DesignArgs", "snake_case", "design_dispatch!"],
+    },
+    Module {
+        name: "graph",
+        source: include_str!("graph.rs"),
+        deps: &[],
+        items: &["Graph"],
+    },
+];
+
+/// Find a module by name.
+///
+/// # Example
+/// ```
+/// use cauly_rust_leetcode_utils::*;
+/// assert_eq!(Some("grid"), find_module("grid").map(|m| m.name));
+/// assert!(find_module("heap").is_none());
+/// ```
+pub fn find_module(name: &str) -> Option<&'static Module> {
+    MODULES.iter().find(|m| m.name == name)
+}
+
+/// The source of a module, or an error naming the known modules.
+pub fn get_module_source(name: &str) -> Result<&'static str, String> {
+    find_module(name).map(|m| m.source).ok_or_else(|| {
+        format!(
+            "unknown module `{}`, expected one of: {}",
+            name,
+            module_names().join(", ")
+        )
+    })
+}
+
+/// The names of all modules.
+pub fn module_names() -> Vec<&'static str> {
+    MODULES.iter().map(|m| m.name).collect()
+}
+
+/// The module defining `item`. Macros can be given with or without their `!`.
+///
+/// # Example
+/// ```
+/// use cauly_rust_leetcode_utils::*;
+/// assert_eq!(Some("union_find"), which_module("UnionFind").map(|m| m.name));
+/// assert_eq!(Some("dp"), which_module("define_dp").map(|m| m.name));
+/// assert_eq!(Some("dp"), which_module("define_dp!").map(|m| m.name));
+/// ```
+pub fn which_module(item: &str) -> Option<&'static Module> {
+    let item = item.trim_end_matches('!');
+    MODULES
+        .iter()
+        .find(|m| m.items.iter().any(|i| i.trim_end_matches('!') == item))
 }
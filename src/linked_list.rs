@@ -0,0 +1,200 @@
+use crate::leetcode_io::{format, parse, LeetCodeValue, Value};
+use std::fmt;
+
+// #region LeetCode ListNode
+#[derive(PartialEq, Eq, Clone)]
+pub struct ListNode {
+    pub val: i32,
+    pub next: Option<Box<ListNode>>,
+}
+
+impl ListNode {
+    #[inline]
+    pub fn new(val: i32) -> Self {
+        ListNode { next: None, val }
+    }
+}
+// #endregion
+
+// #region List ext
+
+/// Prints the values from this node on, e.g. `1 -> 2 -> 3`.
+impl fmt::Debug for ListNode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut node = Some(self);
+        while let Some(n) = node {
+            write!(f, "{}", n.val)?;
+            if n.next.is_some() {
+                write!(f, " -> ")?;
+            }
+            node = n.next.as_deref();
+        }
+        Ok(())
+    }
+}
+
+impl ListNode {
+    pub fn from_vec(values: &[i32]) -> Option<Box<ListNode>> {
+        let mut head = None;
+        for &val in values.iter().rev() {
+            head = Some(Box::new(ListNode { val, next: head }));
+        }
+        head
+    }
+
+    /// Build a list from its LeetCode form, e.g. `[1,2,3]`.
+    pub fn from_string(s: &str) -> Result<Option<Box<ListNode>>, String> {
+        parse(s)
+    }
+}
+
+/// A list is written as the list of its values.
+impl LeetCodeValue for Option<Box<ListNode>> {
+    fn from_value(value: &Value) -> Result<Self, String> {
+        Vec::<i32>::from_value(value).map(|v| ListNode::from_vec(&v))
+    }
+    fn to_value(&self) -> Value {
+        self.to_vec().to_value()
+    }
+}
+
+pub trait BoxListNode: Sized {
+    fn to_vec(&self) -> Vec<i32>;
+    fn to_leetcode_string(&self) -> String;
+    fn values(&self) -> ListValues<'_>;
+    fn nodes(&self) -> ListNodes<'_>;
+    fn len(&self) -> usize;
+    fn is_empty(&self) -> bool;
+    fn reverse(self) -> Self;
+    /// Split after the first `k` nodes.
+    fn split_at(self, k: usize) -> (Self, Self);
+    /// Merge two lists sorted in ascending order into one; equal values of `self` come first.
+    fn merge_sorted(self, other: Self) -> Self;
+    /// The middle node, or the second of the two middle nodes for lists of even length.
+    fn middle(&self) -> Option<&ListNode>;
+    /// Append `other` after the last node.
+    fn append(&mut self, other: Self);
+}
+
+impl BoxListNode for Option<Box<ListNode>> {
+    fn to_vec(&self) -> Vec<i32> {
+        self.values().collect()
+    }
+
+    fn to_leetcode_string(&self) -> String {
+        format(self)
+    }
+
+    fn values(&self) -> ListValues<'_> {
+        ListValues {
+            nodes: self.nodes(),
+        }
+    }
+
+    fn nodes(&self) -> ListNodes<'_> {
+        ListNodes {
+            node: self.as_deref(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.nodes().count()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.is_none()
+    }
+
+    fn reverse(self) -> Self {
+        let mut result = None;
+        let mut rest = self;
+        while let Some(mut node) = rest {
+            rest = node.next.take();
+            node.next = result;
+            result = Some(node);
+        }
+        result
+    }
+
+    fn split_at(mut self, k: usize) -> (Self, Self) {
+        let mut tail = &mut self;
+        for _ in 0..k {
+            match tail {
+                Some(node) => tail = &mut node.next,
+                None => break,
+            }
+        }
+        let rest = tail.take();
+        (self, rest)
+    }
+
+    fn merge_sorted(self, other: Self) -> Self {
+        let mut result = None;
+        let mut tail = &mut result;
+        let (mut a, mut b) = (self, other);
+        loop {
+            let next = match (a.take(), b.take()) {
+                (Some(mut x), Some(y)) if x.val <= y.val => {
+                    a = x.next.take();
+                    b = Some(y);
+                    x
+                }
+                (Some(x), Some(mut y)) => {
+                    b = y.next.take();
+                    a = Some(x);
+                    y
+                }
+                (x, y) => {
+                    *tail = x.or(y);
+                    return result;
+                }
+            };
+            tail = &mut tail.insert(next).next;
+        }
+    }
+
+    fn middle(&self) -> Option<&ListNode> {
+        let mut slow = self.as_deref();
+        let mut fast = self.as_deref();
+        while let Some(next) = fast.and_then(|f| f.next.as_deref()) {
+            slow = slow.and_then(|s| s.next.as_deref());
+            fast = next.next.as_deref();
+        }
+        slow
+    }
+
+    fn append(&mut self, other: Self) {
+        let mut tail = self;
+        while let Some(node) = tail {
+            tail = &mut node.next;
+        }
+        *tail = other;
+    }
+}
+
+/// The values of a list, see [`BoxListNode::values`].
+pub struct ListValues<'a> {
+    nodes: ListNodes<'a>,
+}
+
+impl<'a> Iterator for ListValues<'a> {
+    type Item = i32;
+    fn next(&mut self) -> Option<i32> {
+        self.nodes.next().map(|n| n.val)
+    }
+}
+
+/// The nodes of a list, see [`BoxListNode::nodes`].
+pub struct ListNodes<'a> {
+    node: Option<&'a ListNode>,
+}
+
+impl<'a> Iterator for ListNodes<'a> {
+    type Item = &'a ListNode;
+    fn next(&mut self) -> Option<&'a ListNode> {
+        let node = self.node?;
+        self.node = node.next.as_deref();
+        Some(node)
+    }
+}
+// #endregion
@@ -0,0 +1,199 @@
+use crate::binary_tree::{RefTreeNode, TreeNode};
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+// #region Random
+/// Where the child of each node of [`Rng::skewed_tree`] goes.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Skew {
+    Left,
+    Right,
+    /// Left or right at random, e.g. a zigzag.
+    Random,
+}
+
+/// A small seeded pseudo random generator (splitmix64), for stress testing a solution against a
+/// brute force. The same seed always gives the same numbers.
+///
+/// The tree generators return the tree together with its LeetCode string, which can be pasted
+/// into the custom test box of the problem when the solutions disagree. Values are drawn from
+/// `lo..=hi`.
+///
+/// # Example
+/// ```
+/// use cauly_rust_leetcode_utils::binary_tree::*;
+/// use cauly_rust_leetcode_utils::random::*;
+/// let mut rng = Rng::new(42);
+/// let (tree, s) = rng.tree(10, -5, 5);
+/// assert_eq!(10, tree.node_count());
+/// assert_eq!(s, tree.to_leetcode_string());
+/// let (bst, _) = rng.bst(20, 1, 100);
+/// assert!(bst.is_valid_bst());
+/// assert_eq!("[7,7,7]", rng.perfect_tree(2, 7, 7).1);
+/// assert_eq!(Rng::new(1).range(0, 1000), Rng::new(1).range(0, 1000));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `lo..=hi`.
+    pub fn range(&mut self, lo: i32, hi: i32) -> i32 {
+        assert!(lo <= hi, "empty range {}..={}", lo, hi);
+        let span = (i64::from(hi) - i64::from(lo) + 1) as u64;
+        (i64::from(lo) + (self.next_u64() % span) as i64) as i32
+    }
+
+    /// A number in `0..n`.
+    pub fn below(&mut self, n: usize) -> usize {
+        assert!(n > 0, "empty range 0..0");
+        (self.next_u64() % n as u64) as usize
+    }
+
+    pub fn bool(&mut self) -> bool {
+        self.next_u64() & 1 == 1
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+
+    /// `n` distinct numbers of `lo..=hi` in random order.
+    ///
+    /// # Panics
+    /// If the range has fewer than `n` numbers.
+    pub fn distinct(&mut self, n: usize, lo: i32, hi: i32) -> Vec<i32> {
+        let span = (i64::from(hi) - i64::from(lo) + 1).max(0) as u64;
+        assert!(
+            n as u64 <= span,
+            "{}..={} has fewer than {} numbers",
+            lo,
+            hi,
+            n
+        );
+        if span <= 4 * n as u64 {
+            let mut values: Vec<i32> = (lo..=hi).collect();
+            self.shuffle(&mut values);
+            values.truncate(n);
+            return values;
+        }
+        let mut seen = HashSet::new();
+        let mut values = Vec::with_capacity(n);
+        while values.len() < n {
+            let v = self.range(lo, hi);
+            if seen.insert(v) {
+                values.push(v);
+            }
+        }
+        values
+    }
+
+    /// A tree of `n` nodes, each added as a child of a random node that has room for it.
+    pub fn tree(&mut self, n: usize, lo: i32, hi: i32) -> (Option<Rc<RefCell<TreeNode>>>, String) {
+        let mut nodes = Vec::with_capacity(n);
+        // the free child slots, by node and side
+        let mut slots: Vec<(usize, bool)> = Vec::new();
+        for i in 0..n {
+            nodes.push(Rc::new(RefCell::new(TreeNode::new(self.range(lo, hi)))));
+            if i > 0 {
+                let (parent, is_left) = slots.swap_remove(self.below(slots.len()));
+                link(&nodes[parent], &nodes[i], is_left);
+            }
+            slots.push((i, true));
+            slots.push((i, false));
+        }
+        with_string(nodes.first().cloned())
+    }
+
+    /// A binary search tree of `n` distinct values, inserted in random order.
+    ///
+    /// # Panics
+    /// If the range has fewer than `n` numbers.
+    pub fn bst(&mut self, n: usize, lo: i32, hi: i32) -> (Option<Rc<RefCell<TreeNode>>>, String) {
+        let mut root = None;
+        for val in self.distinct(n, lo, hi) {
+            root.bst_insert(val);
+        }
+        with_string(root)
+    }
+
+    /// A tree of `n` nodes in a single path, the worst case of recursive solutions.
+    pub fn skewed_tree(
+        &mut self,
+        n: usize,
+        lo: i32,
+        hi: i32,
+        skew: Skew,
+    ) -> (Option<Rc<RefCell<TreeNode>>>, String) {
+        let nodes: Vec<_> = (0..n)
+            .map(|_| Rc::new(RefCell::new(TreeNode::new(self.range(lo, hi)))))
+            .collect();
+        for pair in nodes.windows(2) {
+            let is_left = match skew {
+                Skew::Left => true,
+                Skew::Right => false,
+                Skew::Random => self.bool(),
+            };
+            link(&pair[0], &pair[1], is_left);
+        }
+        with_string(nodes.first().cloned())
+    }
+
+    /// A tree of `n` nodes whose levels are full, but the last one which is filled from the
+    /// left.
+    pub fn complete_tree(
+        &mut self,
+        n: usize,
+        lo: i32,
+        hi: i32,
+    ) -> (Option<Rc<RefCell<TreeNode>>>, String) {
+        let nodes: Vec<_> = (0..n)
+            .map(|_| Rc::new(RefCell::new(TreeNode::new(self.range(lo, hi)))))
+            .collect();
+        for i in 1..n {
+            link(&nodes[(i - 1) / 2], &nodes[i], i % 2 == 1);
+        }
+        with_string(nodes.first().cloned())
+    }
+
+    /// A tree of `height` full levels, `2^height - 1` nodes.
+    pub fn perfect_tree(
+        &mut self,
+        height: u32,
+        lo: i32,
+        hi: i32,
+    ) -> (Option<Rc<RefCell<TreeNode>>>, String) {
+        self.complete_tree((1 << height) - 1, lo, hi)
+    }
+}
+
+fn link(parent: &Rc<RefCell<TreeNode>>, child: &Rc<RefCell<TreeNode>>, is_left: bool) {
+    let mut p = parent.borrow_mut();
+    if is_left {
+        p.left = Some(Rc::clone(child));
+    } else {
+        p.right = Some(Rc::clone(child));
+    }
+}
+
+fn with_string(tree: Option<Rc<RefCell<TreeNode>>>) -> (Option<Rc<RefCell<TreeNode>>>, String) {
+    let s = tree.to_leetcode_string();
+    (tree, s)
+}
+// #endregion
@@ -0,0 +1,215 @@
+use super::{find_problem, Options, LIB_CRATE};
+use crate::{find_module, module_names, which_module, MODULES};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const USAGE: &str = "Usage: rlfmt [OPTIONS] [FILE | PROBLEM]
+       rlfmt scaffold [-o <FILE>] <EXAMPLES> [FILE | PROBLEM]
+
+Flatten FILE (default: lib.rs or src/lib.rs) and the library modules it uses into a single
+source for the leetcode editor. PROBLEM is a problem id such as `1` for src/p0001_*.rs, or a
+glob matching one file of src/ or src/bin/.
+
+`rlfmt scaffold` writes a test module with one test per example found in the EXAMPLES text
+(`Input: nums = [1,2], target = 3 Output: [0,1]`), calling the matching method of FILE.
+
+Options:
+  -o, --output <FILE>        write the result to FILE instead of stdout
+      --inline[=MODULES]     inline MODULES (comma separated, default: all), the default
+      --no-inline[=MODULES]  keep the imports of MODULES (default: all) instead of inlining
+      --no-prune             inline whole modules, not only the items the solution reaches
+      --keep-prints          don't comment out print! and println!
+      --keep-tests           keep #[cfg(test)] and #[test] items
+      --strip-comments       remove all comments
+      --minify               remove comments and blank lines from the inlined library code
+      --verify               compile the result with the local rustc
+      --watch                flatten again whenever the input or its modules change
+      --list                 list the library modules with their dependencies and items
+      --which <ITEM>         print the library module defining ITEM
+  -h, --help                 print this help
+  -V, --version              print the version
+";
+
+/// The command line of `rlfmt`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Args {
+    pub input: Option<String>,
+    pub output: Option<String>,
+    pub verify: bool,
+    pub watch: bool,
+    pub list: bool,
+    /// The examples file of `rlfmt scaffold`.
+    pub scaffold: Option<String>,
+    pub which: Option<String>,
+    pub help: bool,
+    pub version: bool,
+    pub options: Options,
+}
+
+impl Args {
+    /// The input file: the file given, or the problem file matching it if it isn't a path to a
+    /// file (see [`find_problem`]), or `lib.rs` / `src/lib.rs` if none was given.
+    pub fn input_file(&self) -> Result<PathBuf, String> {
+        match &self.input {
+            Some(input) if Path::new(input).is_file() => Ok(PathBuf::from(input)),
+            // a path rather than a problem id or glob, report why it can't be read
+            Some(input)
+                if (input.contains('/') || input.ends_with(".rs"))
+                    && !input.contains(['*', '?']) =>
+            {
+                fs::metadata(input)
+                    .map(|_| PathBuf::from(input))
+                    .map_err(|e| format!("can't read `{}`: {}", input, e))
+            }
+            Some(input) => find_problem(Path::new("."), input),
+            None => ["lib.rs", "src/lib.rs"]
+                .iter()
+                .map(PathBuf::from)
+                .find(|f| f.is_file())
+                .ok_or_else(|| {
+                    "no input file given, and neither lib.rs nor src/lib.rs exists".to_string()
+                }),
+        }
+    }
+}
+
+/// Parse the arguments of `rlfmt`, without the program name.
+///
+/// # Example
+/// ```
+/// use cauly_rust_leetcode_utils::rlfmt::*;
+/// let args = parse_args(["-o", "out.rs", "--no-inline", "--inline=grid", "main.rs"]).unwrap();
+/// assert_eq!(Some("main.rs".to_string()), args.input);
+/// assert_eq!(Some("out.rs".to_string()), args.output);
+/// assert!(!args.options.not_inlined.contains(&"grid"));
+/// assert!(args.options.not_inlined.contains(&"dp"));
+/// assert!(parse_args(["--no-inline=foo"]).is_err());
+/// ```
+pub fn parse_args<I, S>(args: I) -> Result<Args, String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut result = Args::default();
+    let mut scaffold = false;
+    let mut args = args.into_iter().map(|a| a.as_ref().to_string());
+    while let Some(arg) = args.next() {
+        let (flag, value) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value)),
+            _ => (arg.clone(), None),
+        };
+        match flag.as_str() {
+            "-o" | "--output" => {
+                let file = match value {
+                    Some(v) => v.to_string(),
+                    None => args
+                        .next()
+                        .ok_or_else(|| format!("`{}` expects a file name", flag))?,
+                };
+                result.output = Some(file);
+            }
+            "--inline" | "--no-inline" => {
+                let modules = match value {
+                    Some(v) => module_list(v)?,
+                    None => module_names(),
+                };
+                let not_inlined = &mut result.options.not_inlined;
+                not_inlined.retain(|m| !modules.contains(m));
+                if flag == "--no-inline" {
+                    not_inlined.extend(modules);
+                }
+            }
+            "--which" => {
+                let item = match value {
+                    Some(v) => v.to_string(),
+                    None => args
+                        .next()
+                        .ok_or_else(|| format!("`{}` expects an item name", flag))?,
+                };
+                result.which = Some(item);
+            }
+            "--list" => result.list = true,
+            "--no-prune" => result.options.prune = false,
+            "--keep-prints" => result.options.keep_prints = true,
+            "--keep-tests" => result.options.keep_tests = true,
+            "--strip-comments" => result.options.strip_comments = true,
+            "--minify" => result.options.minify = true,
+            "--verify" => result.verify = true,
+            "--watch" => result.watch = true,
+            "-h" | "--help" => result.help = true,
+            "-V" | "--version" => result.version = true,
+            _ if arg.starts_with('-') => {
+                return Err(format!("unknown option `{}`", arg));
+            }
+            "scaffold" if !scaffold && result.input.is_none() => scaffold = true,
+            _ if scaffold && result.scaffold.is_none() => result.scaffold = Some(arg),
+            _ => {
+                if let Some(input) = &result.input {
+                    return Err(format!(
+                        "more than one input file: `{}` and `{}`",
+                        input, arg
+                    ));
+                }
+                result.input = Some(arg);
+            }
+        }
+    }
+    if scaffold && result.scaffold.is_none() {
+        return Err("`scaffold` expects a file with the examples".to_string());
+    }
+    Ok(result)
+}
+
+fn module_list(list: &str) -> Result<Vec<&'static str>, String> {
+    list.split(',')
+        .filter(|m| !m.is_empty())
+        .map(|m| {
+            find_module(m).map(|m| m.name).ok_or_else(|| {
+                format!(
+                    "unknown module `{}`, expected one of: {}",
+                    m,
+                    module_names().join(", ")
+                )
+            })
+        })
+        .collect()
+}
+
+/// The output of `rlfmt --list`: one line per module with its items and dependencies.
+pub fn module_table() -> String {
+    let width = MODULES.iter().map(|m| m.name.len()).max().unwrap_or(0);
+    let mut result = String::new();
+    for module in MODULES {
+        result.push_str(&format!(
+            "{:width$}  {}",
+            module.name,
+            module.items.join(", "),
+            width = width
+        ));
+        if !module.deps.is_empty() {
+            result.push_str(&format!(" (uses {})", module.deps.join(", ")));
+        }
+        result.push('\n');
+    }
+    result
+}
+
+/// The output of `rlfmt --which <item>`: the import of the item.
+///
+/// # Example
+/// ```
+/// use cauly_rust_leetcode_utils::rlfmt::*;
+/// assert_eq!(Ok("use cauly_rust_leetcode_utils::grid::Grid;".to_string()), which("Grid"));
+/// assert_eq!(Ok("use cauly_rust_leetcode_utils::define_dp;".to_string()), which("define_dp!"));
+/// assert!(which("Heap").is_err());
+/// ```
+pub fn which(item: &str) -> Result<String, String> {
+    let module =
+        which_module(item).ok_or_else(|| format!("no library module defines `{}`", item))?;
+    let item = item.trim_end_matches('!');
+    if module.items.contains(&format!("{}!", item).as_str()) {
+        Ok(format!("use {}::{};", LIB_CRATE, item))
+    } else {
+        Ok(format!("use {}::{}::{};", LIB_CRATE, module.name, item))
+    }
+}
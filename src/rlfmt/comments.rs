@@ -0,0 +1,73 @@
+use super::lexer::{tokenize, Token, TokenKind};
+use super::source_map::Flattened;
+
+/// Remove the comments for which `remove` returns true. Lines left with nothing but whitespace
+/// are dropped, all other lines keep their origin.
+///
+/// # Example
+/// ```
+/// use cauly_rust_leetcode_utils::rlfmt::*;
+/// let mut code = Flattened::default();
+/// code.push("// a\nlet a = 1; // one\n/* b\n c */ let b = 2;\n", Origin::Solution(1));
+/// let result = strip_comments(&code, |_| true);
+/// assert_eq!("let a = 1;\n let b = 2;\n", result.code);
+/// assert_eq!(vec![Origin::Solution(2), Origin::Solution(4)], result.origins);
+/// ```
+pub fn strip_comments<F>(flattened: &Flattened, remove: F) -> Flattened
+where
+    F: Fn(&Token) -> bool,
+{
+    let mut stripped = String::new();
+    for token in tokenize(&flattened.code) {
+        if token.is_trivia() && token.kind != TokenKind::Whitespace && remove(&token) {
+            // keep the line breaks so that lines still line up with the original
+            stripped.push_str(&"\n".repeat(token.text.matches('\n').count()));
+        } else {
+            stripped.push_str(token.text);
+        }
+    }
+
+    let mut result = Flattened::default();
+    let original = flattened.code.split_inclusive('\n');
+    for ((before, after), origin) in original
+        .zip(stripped.split_inclusive('\n'))
+        .zip(flattened.origins.iter())
+    {
+        if after.trim().is_empty() && !before.trim().is_empty() {
+            continue;
+        }
+        let line = if after.ends_with('\n') {
+            format!("{}\n", after.trim_end())
+        } else {
+            after.trim_end().to_string()
+        };
+        result.push(&line, origin.clone());
+    }
+    result
+}
+
+/// Remove all comments, doc comments included, and all blank lines.
+///
+/// # Example
+/// ```
+/// use cauly_rust_leetcode_utils::rlfmt::*;
+/// let mut code = Flattened::default();
+/// code.push("/// A\n/// ```\n/// A::new();\n/// ```\npub struct A;\n\nimpl A {} // empty\n", Origin::Library("a", Some(1)));
+/// let result = minify(&code);
+/// assert_eq!("pub struct A;\nimpl A {}\n", result.code);
+/// assert_eq!(vec![Origin::Library("a", Some(5)), Origin::Library("a", Some(7))], result.origins);
+/// ```
+pub fn minify(flattened: &Flattened) -> Flattened {
+    let stripped = strip_comments(flattened, |_| true);
+    let mut result = Flattened::default();
+    for (line, origin) in stripped
+        .code
+        .split_inclusive('\n')
+        .zip(stripped.origins.iter())
+    {
+        if !line.trim().is_empty() {
+            result.push(line, origin.clone());
+        }
+    }
+    result
+}
@@ -0,0 +1,187 @@
+use super::lexer::{tokenize, Token};
+use super::use_tree::find_use_statements;
+
+/// Find the modules in `modules` that `source` refers to through `crate::<module>` paths,
+/// in order of first appearance.
+///
+/// # Example
+/// ```
+/// use cauly_rust_leetcode_utils::rlfmt::*;
+/// let src = "use crate::{union_find::UnionFind4Usize, dp::*};\nfn f() { crate::grid::Grid::<i32>::new(1, 1); }";
+/// assert_eq!(vec!["union_find", "dp", "grid"], module_dependencies(src, &["grid", "union_find", "dp"]));
+/// ```
+pub fn module_dependencies<'a>(source: &str, modules: &[&'a str]) -> Vec<&'a str> {
+    let mut result = Vec::new();
+    let mut push = |name: &str| {
+        if let Some(&m) = modules.iter().find(|&&m| m == name) {
+            if !result.contains(&m) {
+                result.push(m);
+            }
+        }
+    };
+    let mut statements = find_use_statements(source).unwrap_or_default().into_iter();
+    let mut next_statement = statements.next();
+    let tokens: Vec<Token> = tokenize(source)
+        .into_iter()
+        .filter(|t| !t.is_trivia())
+        .collect();
+    for (i, t) in tokens.iter().enumerate() {
+        if let Some(statement) = &next_statement {
+            if t.start >= statement.start {
+                for path in &statement.paths {
+                    if path.segments.len() > 1 && path.segments[0] == "crate" {
+                        push(&path.segments[1]);
+                    }
+                }
+                next_statement = statements.next();
+            }
+        }
+        if is_crate_path(&tokens, i) {
+            push(tokens[i + 3].text);
+        }
+    }
+    result
+}
+
+/// `crate` followed by `::` and an identifier.
+fn is_crate_path(tokens: &[Token], i: usize) -> bool {
+    tokens[i].is_ident("crate")
+        && tokens.len() > i + 3
+        && tokens[i + 1].is_punct(':')
+        && tokens[i + 2].is_punct(':')
+        && tokens[i + 1].end() == tokens[i + 2].start
+}
+
+/// Collect `roots` and every module they depend on, ordered so that each module comes after
+/// all of its dependencies. `lookup` returns the source of a module, or None if the name is
+/// not a library module; `modules` is the list of all library module names.
+///
+/// # Example
+/// ```
+/// use cauly_rust_leetcode_utils::rlfmt::*;
+/// let lookup = |name: &str| match name {
+///     "a" => Some("use crate::b::B;"),
+///     "b" => Some("use crate::c::C;"),
+///     "c" => Some("pub struct C;"),
+///     _ => None,
+/// };
+/// assert_eq!(Ok(vec!["c", "b", "a"]), resolve_dependencies(&["a"], &["a", "b", "c"], lookup));
+/// ```
+pub fn resolve_dependencies<'a, F>(
+    roots: &[&'a str],
+    modules: &[&'a str],
+    lookup: F,
+) -> Result<Vec<&'a str>, String>
+where
+    F: Fn(&str) -> Option<&'a str>,
+{
+    let mut result = Vec::new();
+    let mut visiting = Vec::new();
+    for &root in roots {
+        visit(root, modules, &lookup, &mut visiting, &mut result)?;
+    }
+    Ok(result)
+}
+
+fn visit<'a, F>(
+    name: &'a str,
+    modules: &[&'a str],
+    lookup: &F,
+    visiting: &mut Vec<&'a str>,
+    result: &mut Vec<&'a str>,
+) -> Result<(), String>
+where
+    F: Fn(&str) -> Option<&'a str>,
+{
+    if result.contains(&name) {
+        return Ok(());
+    }
+    if visiting.contains(&name) {
+        return Err(format!(
+            "circular module dependency: {} -> {}",
+            visiting.join(" -> "),
+            name
+        ));
+    }
+    let source = lookup(name).ok_or_else(|| format!("unknown module `{}`", name))?;
+    visiting.push(name);
+    for dep in module_dependencies(source, modules) {
+        if dep != name {
+            visit(dep, modules, lookup, visiting, result)?;
+        }
+    }
+    visiting.pop();
+    result.push(name);
+    Ok(())
+}
+
+/// Rewrite a library module so that it can be pasted next to the other inlined modules:
+/// `use crate::...;` statements between library modules are dropped, and
+/// `crate::<module>::` / `$crate::<module>::` path prefixes are removed.
+///
+/// # Example
+/// ```
+/// use cauly_rust_leetcode_utils::rlfmt::*;
+/// let src = "use crate::union_find::{\n    UnionFind4Usize,\n};\nuse std::rc::Rc;\nlet uf = crate::union_find::UnionFind4Usize::new(1);\n";
+/// assert_eq!(
+///     "use std::rc::Rc;\nlet uf = UnionFind4Usize::new(1);\n",
+///     strip_crate_paths(src, &["union_find"])
+/// );
+/// ```
+pub fn strip_crate_paths(source: &str, modules: &[&str]) -> String {
+    let mut removed: Vec<(usize, usize)> = find_use_statements(source)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|s| {
+            !s.paths.is_empty()
+                && s.paths
+                    .iter()
+                    .all(|p| p.segments.len() > 1 && p.segments[0] == "crate")
+        })
+        .map(|s| whole_lines(source, s.start, s.end))
+        .collect();
+    let tokens: Vec<Token> = tokenize(source)
+        .into_iter()
+        .filter(|t| !t.is_trivia())
+        .collect();
+    for i in 0..tokens.len() {
+        if is_crate_path(&tokens, i)
+            && modules.contains(&tokens[i + 3].text)
+            && tokens.get(i + 4).is_some_and(|t| t.is_punct(':'))
+            && tokens.get(i + 5).is_some_and(|t| t.is_punct(':'))
+        {
+            let start = if i > 0 && tokens[i - 1].is_punct('$') {
+                tokens[i - 1].start
+            } else {
+                tokens[i].start
+            };
+            removed.push((start, tokens[i + 5].end()));
+        }
+    }
+    removed.sort_unstable();
+
+    let mut result = String::new();
+    let mut pos = 0;
+    for (start, end) in removed {
+        if start >= pos {
+            result.push_str(&source[pos..start]);
+            pos = end;
+        }
+    }
+    result.push_str(&source[pos..]);
+    result
+}
+
+/// Extend `start..end` to whole lines when nothing but whitespace shares those lines with it,
+/// so that removing the range doesn't leave an empty line behind.
+pub fn whole_lines(source: &str, start: usize, end: usize) -> (usize, usize) {
+    let line_start = source[..start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = source[end..]
+        .find('\n')
+        .map_or(source.len(), |i| end + i + 1);
+    if source[line_start..start].trim().is_empty() && source[end..line_end].trim().is_empty() {
+        (line_start, line_end)
+    } else {
+        (start, end)
+    }
+}
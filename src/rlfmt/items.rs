@@ -0,0 +1,281 @@
+use super::lexer::{matching_close, tokenize, Token, TokenKind};
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum ItemKind {
+    Use,
+    ExternCrate,
+    Struct,
+    Enum,
+    Union,
+    Trait,
+    Impl,
+    Fn,
+    Const,
+    Static,
+    Type,
+    Mod,
+    MacroRules,
+    MacroCall,
+    Other,
+}
+
+/// An item of rust source, e.g. a struct, an impl block, or a method inside an impl block.
+/// `start..end` is its byte range, including doc comments and attributes.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Item {
+    pub kind: ItemKind,
+    pub name: Option<String>,
+    pub start: usize,
+    pub end: usize,
+    /// The attributes of the item, e.g. `#[cfg(test)]`.
+    pub attrs: Vec<String>,
+    /// The byte range inside the braces of an impl, trait or mod.
+    pub body: Option<(usize, usize)>,
+    /// For impls, the identifiers of the implemented trait.
+    pub impl_trait: Vec<String>,
+    /// For impls, the identifiers of the self type.
+    pub impl_self: Vec<String>,
+}
+
+/// Split `source[range]` into items. Use the whole source for the top level items of a file,
+/// or the `body` of an impl or trait for its methods.
+///
+/// # Example
+/// ```
+/// use cauly_rust_leetcode_utils::rlfmt::*;
+/// let src = "/// doc\n#[derive(Clone)]\npub struct A(u32);\n// comment\nimpl<T: Fn() -> i32> Tr for B<T> {\n    fn f() {}\n}\n";
+/// let items = parse_items(src, (0, src.len()));
+/// assert_eq!(2, items.len());
+/// assert_eq!((ItemKind::Struct, Some("A".to_string())), (items[0].kind, items[0].name.clone()));
+/// assert_eq!("/// doc\n#[derive(Clone)]\npub struct A(u32);", &src[items[0].start..items[0].end]);
+/// assert_eq!(vec!["Tr"], items[1].impl_trait);
+/// assert_eq!(vec!["B", "T"], items[1].impl_self);
+/// let methods = parse_items(src, items[1].body.unwrap());
+/// assert_eq!(Some("f".to_string()), methods[0].name);
+/// ```
+pub fn parse_items(source: &str, range: (usize, usize)) -> Vec<Item> {
+    let all = tokenize(source);
+    let toks: Vec<usize> = (0..all.len())
+        .filter(|&i| !all[i].is_trivia() && all[i].start >= range.0 && all[i].end() <= range.1)
+        .collect();
+    let mut position = vec![0; all.len()];
+    for (p, &i) in toks.iter().enumerate() {
+        position[i] = p;
+    }
+    let tok = |p: usize| toks.get(p).map(|&i| all[i]);
+    let close = |p: usize| position[matching_close(&all, toks[p]).unwrap_or(toks[toks.len() - 1])];
+
+    let mut result = Vec::new();
+    let mut p = 0;
+    while p < toks.len() {
+        let first = p;
+        if tok(p).is_some_and(|t| t.is_punct('#'))
+            && tok(p + 1).is_some_and(|t| t.is_punct('!'))
+            && tok(p + 2).is_some_and(|t| t.is_punct('['))
+        {
+            // an inner attribute such as `#![allow(dead_code)]`
+            let end = close(p + 2);
+            result.push(Item {
+                kind: ItemKind::Other,
+                name: None,
+                start: all[toks[p]].start,
+                end: all[toks[end]].end(),
+                attrs: Vec::new(),
+                body: None,
+                impl_trait: Vec::new(),
+                impl_self: Vec::new(),
+            });
+            p = end + 1;
+            continue;
+        }
+        let mut attrs = Vec::new();
+        while tok(p).is_some_and(|t| t.is_punct('#')) && tok(p + 1).is_some_and(|t| t.is_punct('['))
+        {
+            let end = close(p + 1);
+            attrs.push(source[all[toks[p]].start..all[toks[end]].end()].to_string());
+            p = end + 1;
+        }
+        if tok(p).is_some_and(|t| t.is_ident("pub")) {
+            p += 1;
+            if tok(p).is_some_and(|t| t.is_punct('(')) {
+                p = close(p) + 1;
+            }
+        }
+        loop {
+            match tok(p) {
+                Some(t) if t.is_ident("default") || t.is_ident("async") || t.is_ident("unsafe") => {
+                    p += 1
+                }
+                Some(t)
+                    if t.is_ident("const")
+                        && tok(p + 1)
+                            .is_some_and(|t| t.kind == TokenKind::Ident && t.text != "_")
+                        && !tok(p + 2).is_some_and(|t| t.is_punct(':')) =>
+                {
+                    p += 1
+                }
+                Some(t)
+                    if t.is_ident("extern") && !tok(p + 1).is_some_and(|t| t.is_ident("crate")) =>
+                {
+                    p += 1;
+                    if tok(p).is_some_and(|t| t.kind == TokenKind::Literal) {
+                        p += 1;
+                    }
+                }
+                _ => break,
+            }
+        }
+
+        let keyword = match tok(p) {
+            Some(t) => t,
+            None => break,
+        };
+        let next_is_bang = tok(p + 1).is_some_and(|t| t.is_punct('!'));
+        let kind = match keyword.text {
+            "use" => ItemKind::Use,
+            "extern" => ItemKind::ExternCrate,
+            "struct" => ItemKind::Struct,
+            "enum" => ItemKind::Enum,
+            "union" if tok(p + 1).is_some_and(|t| t.kind == TokenKind::Ident) => ItemKind::Union,
+            "trait" => ItemKind::Trait,
+            "impl" => ItemKind::Impl,
+            "fn" => ItemKind::Fn,
+            "const" => ItemKind::Const,
+            "static" => ItemKind::Static,
+            "type" => ItemKind::Type,
+            "mod" => ItemKind::Mod,
+            "macro_rules" if next_is_bang => ItemKind::MacroRules,
+            _ if keyword.kind == TokenKind::Ident && next_is_bang => ItemKind::MacroCall,
+            _ => ItemKind::Other,
+        };
+        let name = match kind {
+            ItemKind::MacroCall => Some(keyword.text.to_string()),
+            ItemKind::MacroRules => tok(p + 2).map(|t| t.text.to_string()),
+            ItemKind::Use | ItemKind::ExternCrate | ItemKind::Impl | ItemKind::Other => None,
+            _ => tok(p + 1)
+                .filter(|t| t.kind == TokenKind::Ident)
+                .map(|t| t.text.to_string()),
+        };
+        let ends_at_semicolon = matches!(
+            kind,
+            ItemKind::Use
+                | ItemKind::ExternCrate
+                | ItemKind::Const
+                | ItemKind::Static
+                | ItemKind::Type
+                | ItemKind::Other
+        );
+
+        // find the end of the item: a `;` or the closing brace of its body
+        let mut body = None;
+        let mut end = p;
+        while end < toks.len() {
+            let t = all[toks[end]];
+            if t.is_punct(';') {
+                break;
+            } else if t.is_punct('{') && !ends_at_semicolon {
+                let close_brace = close(end);
+                body = Some((t.end(), all[toks[close_brace]].start));
+                end = close_brace;
+                break;
+            } else if t.is_punct('(') || t.is_punct('[') || t.is_punct('{') {
+                end = close(end);
+                if kind == ItemKind::Other && t.is_punct('{') {
+                    break;
+                }
+            }
+            end += 1;
+        }
+        let end = end.min(toks.len() - 1);
+
+        let (impl_trait, impl_self) = if kind == ItemKind::Impl {
+            impl_header(&all, &toks[p + 1..end])
+        } else {
+            (Vec::new(), Vec::new())
+        };
+
+        // include the doc comments right before the item
+        let mut start_index = toks[first];
+        while start_index > 0
+            && (all[start_index - 1].kind == TokenKind::Whitespace
+                || (all[start_index - 1].is_doc_comment()
+                    && !all[start_index - 1].text.starts_with("//!")))
+            && all[start_index - 1].start >= range.0
+        {
+            start_index -= 1;
+        }
+        while all[start_index].kind == TokenKind::Whitespace {
+            start_index += 1;
+        }
+
+        result.push(Item {
+            kind,
+            name,
+            start: all[start_index].start,
+            end: all[toks[end]].end(),
+            attrs,
+            body: if matches!(kind, ItemKind::Impl | ItemKind::Trait | ItemKind::Mod) {
+                body
+            } else {
+                None
+            },
+            impl_trait,
+            impl_self,
+        });
+        p = end + 1;
+    }
+    result
+}
+
+/// Split the header of an impl (the tokens between `impl` and its body) into the identifiers of
+/// the trait and of the self type.
+fn impl_header(all: &[Token], toks: &[usize]) -> (Vec<String>, Vec<String>) {
+    let mut p = 0;
+    // skip the generic parameters of the impl
+    if toks.first().is_some_and(|&i| all[i].is_punct('<')) {
+        let mut depth = 0;
+        while p < toks.len() {
+            let t = all[toks[p]];
+            if t.is_punct('<') {
+                depth += 1;
+            } else if t.is_punct('>') && !(p > 0 && all[toks[p - 1]].is_punct('-')) {
+                depth -= 1;
+            }
+            p += 1;
+            if depth == 0 {
+                break;
+            }
+        }
+    }
+    let mut first = Vec::new();
+    let mut second = Vec::new();
+    let mut has_for = false;
+    for &i in &toks[p..] {
+        let t = all[i];
+        if t.is_ident("where") || t.is_punct('{') {
+            break;
+        } else if t.is_ident("for") && !has_for {
+            has_for = true;
+        } else if t.kind == TokenKind::Ident && !t.is_ident("dyn") {
+            if has_for {
+                second.push(t.text.to_string());
+            } else {
+                first.push(t.text.to_string());
+            }
+        }
+    }
+    if has_for {
+        (first, second)
+    } else {
+        (Vec::new(), first)
+    }
+}
+
+/// All identifiers in `source[range]`, excluding comments.
+pub fn idents(source: &str, range: (usize, usize)) -> Vec<&str> {
+    tokenize(&source[range.0..range.1])
+        .into_iter()
+        .filter(|t| t.kind == TokenKind::Ident)
+        .map(|t| t.text)
+        .collect()
+}
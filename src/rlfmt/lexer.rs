@@ -0,0 +1,242 @@
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum TokenKind {
+    Whitespace,
+    LineComment,
+    BlockComment,
+    Ident,
+    Lifetime,
+    Literal,
+    Punct,
+}
+
+/// A token of rust source. Tokens keep their text and byte offset, so that concatenating the
+/// texts of all tokens gives back the original source.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct Token<'a> {
+    pub kind: TokenKind,
+    pub text: &'a str,
+    pub start: usize,
+}
+
+impl<'a> Token<'a> {
+    pub fn end(&self) -> usize {
+        self.start + self.text.len()
+    }
+
+    /// Whitespace and comments.
+    pub fn is_trivia(&self) -> bool {
+        matches!(
+            self.kind,
+            TokenKind::Whitespace | TokenKind::LineComment | TokenKind::BlockComment
+        )
+    }
+
+    /// `///`, `//!`, `/** */` and `/*! */` comments.
+    pub fn is_doc_comment(&self) -> bool {
+        match self.kind {
+            TokenKind::LineComment => {
+                (self.text.starts_with("///") && !self.text.starts_with("////"))
+                    || self.text.starts_with("//!")
+            }
+            TokenKind::BlockComment => {
+                (self.text.starts_with("/**")
+                    && !self.text.starts_with("/***")
+                    && self.text != "/**/")
+                    || self.text.starts_with("/*!")
+            }
+            _ => false,
+        }
+    }
+
+    pub fn is_ident(&self, s: &str) -> bool {
+        self.kind == TokenKind::Ident && self.text == s
+    }
+
+    pub fn is_punct(&self, c: char) -> bool {
+        self.kind == TokenKind::Punct && self.text.starts_with(c)
+    }
+}
+
+/// Split rust source into tokens. Never fails: unterminated literals and comments simply run
+/// to the end of the source.
+///
+/// # Example
+/// ```
+/// use cauly_rust_leetcode_utils::rlfmt::*;
+/// let tokens = tokenize("let c = 'a'; // done");
+/// let kinds: Vec<TokenKind> = tokens.iter().filter(|t| !t.is_trivia()).map(|t| t.kind).collect();
+/// assert_eq!(
+///     vec![TokenKind::Ident, TokenKind::Ident, TokenKind::Punct, TokenKind::Literal, TokenKind::Punct],
+///     kinds
+/// );
+/// assert_eq!("let c = 'a'; // done", tokens.iter().map(|t| t.text).collect::<String>());
+/// ```
+pub fn tokenize(source: &str) -> Vec<Token<'_>> {
+    let bytes = source.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let start = i;
+        let c = source[i..].chars().next().unwrap();
+        let kind = if c.is_whitespace() {
+            i += source[i..]
+                .find(|c: char| !c.is_whitespace())
+                .unwrap_or(source.len() - i);
+            TokenKind::Whitespace
+        } else if source[i..].starts_with("//") {
+            i += source[i..].find('\n').unwrap_or(source.len() - i);
+            TokenKind::LineComment
+        } else if source[i..].starts_with("/*") {
+            i = skip_block_comment(bytes, i);
+            TokenKind::BlockComment
+        } else if let Some(end) = raw_string_end(bytes, i) {
+            i = end;
+            TokenKind::Literal
+        } else if c == '"' || ((c == 'b' || c == 'c') && bytes.get(i + 1) == Some(&b'"')) {
+            i = skip_quoted(bytes, if c == '"' { i } else { i + 1 }, b'"');
+            TokenKind::Literal
+        } else if c == 'b' && bytes.get(i + 1) == Some(&b'\'') {
+            i = skip_quoted(bytes, i + 1, b'\'');
+            TokenKind::Literal
+        } else if c == '\'' {
+            let mut chars = source[i + 1..].chars();
+            match (chars.next(), chars.next()) {
+                (Some('\\'), _) => {
+                    i = skip_quoted(bytes, i, b'\'');
+                    TokenKind::Literal
+                }
+                (Some(c1), Some('\'')) => {
+                    i += 1 + c1.len_utf8() + 1;
+                    TokenKind::Literal
+                }
+                _ => {
+                    i += 1 + ident_len(&source[i + 1..]);
+                    TokenKind::Lifetime
+                }
+            }
+        } else if c.is_ascii_digit() {
+            i = skip_number(bytes, i);
+            TokenKind::Literal
+        } else if c == '_' || c.is_alphabetic() {
+            if source[i..].starts_with("r#") {
+                i += 2;
+            }
+            i += ident_len(&source[i..]);
+            TokenKind::Ident
+        } else {
+            i += c.len_utf8();
+            TokenKind::Punct
+        };
+        tokens.push(Token {
+            kind,
+            text: &source[start..i],
+            start,
+        });
+    }
+    tokens
+}
+
+fn ident_len(s: &str) -> usize {
+    s.find(|c: char| !(c == '_' || c.is_alphanumeric()))
+        .unwrap_or(s.len())
+}
+
+fn skip_block_comment(bytes: &[u8], mut i: usize) -> usize {
+    let mut depth = 0;
+    while i < bytes.len() {
+        if bytes[i..].starts_with(b"/*") {
+            depth += 1;
+            i += 2;
+        } else if bytes[i..].starts_with(b"*/") {
+            depth -= 1;
+            i += 2;
+            if depth == 0 {
+                return i;
+            }
+        } else {
+            i += 1;
+        }
+    }
+    bytes.len()
+}
+
+/// `i` points to the opening quote; returns the index after the closing quote.
+fn skip_quoted(bytes: &[u8], mut i: usize, quote: u8) -> usize {
+    i += 1;
+    while i < bytes.len() {
+        if bytes[i] == b'\\' {
+            i += 2;
+        } else if bytes[i] == quote {
+            return i + 1;
+        } else {
+            i += 1;
+        }
+    }
+    bytes.len()
+}
+
+/// If a raw string (`r"..."`, `r#"..."#`, `br"..."`) starts at `i`, returns its end.
+fn raw_string_end(bytes: &[u8], i: usize) -> Option<usize> {
+    let mut j = i;
+    if bytes.get(j) == Some(&b'b') || bytes.get(j) == Some(&b'c') {
+        j += 1;
+    }
+    if bytes.get(j) != Some(&b'r') {
+        return None;
+    }
+    j += 1;
+    let hashes = bytes[j..].iter().take_while(|&&b| b == b'#').count();
+    j += hashes;
+    if bytes.get(j) != Some(&b'"') {
+        return None;
+    }
+    j += 1;
+    while j < bytes.len() {
+        if bytes[j] == b'"' && bytes[j + 1..].iter().take_while(|&&b| b == b'#').count() >= hashes {
+            return Some(j + 1 + hashes);
+        }
+        j += 1;
+    }
+    Some(bytes.len())
+}
+
+fn skip_number(bytes: &[u8], start: usize) -> usize {
+    let mut i = start;
+    while i < bytes.len() {
+        let b = bytes[i];
+        let is_fraction = b == b'.' && bytes.get(i + 1).is_some_and(|b| b.is_ascii_digit());
+        let is_exponent_sign = (b == b'+' || b == b'-')
+            && (bytes[i - 1] == b'e' || bytes[i - 1] == b'E')
+            && bytes[start..i - 1]
+                .iter()
+                .all(|&b| b.is_ascii_digit() || b == b'_' || b == b'.');
+        if b.is_ascii_alphanumeric() || b == b'_' || is_fraction || is_exponent_sign {
+            i += 1;
+        } else {
+            break;
+        }
+    }
+    i
+}
+
+/// Find the index of the token closing the bracket opened at `open`, skipping trivia and
+/// counting all of `()`, `[]` and `{}`.
+pub fn matching_close(tokens: &[Token], open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, t) in tokens.iter().enumerate().skip(open) {
+        if t.kind != TokenKind::Punct {
+            continue;
+        }
+        match t.text {
+            "(" | "[" | "{" => depth += 1,
+            ")" | "]" | "}" => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
@@ -0,0 +1,189 @@
+use super::lexer::{matching_close, tokenize, Token, TokenKind};
+use super::strip::Edit;
+
+/// Expand the `define_dp!` invocations of a solution into the `impl DPIndexer` blocks they
+/// stand for, so that the submission needs neither the macro nor its definition. Lengths that
+/// are not a single literal, name or path are put in parentheses where they are multiplied.
+/// Nothing is expanded if the solution defines a `define_dp` macro itself.
+///
+/// # Example
+/// ```
+/// use cauly_rust_leetcode_utils::rlfmt::*;
+/// let src = "define_dp! {Dp, i: 10, j: n + 1}\n";
+/// let edits = expand_define_dp(src).unwrap();
+/// assert_eq!(
+///     "impl DPIndexer for Dp {
+///     fn index(&self) -> usize {
+///         self.i * (n + 1) + self.j
+///     }
+///     fn sizes() -> Vec<usize> {
+///         vec![10, n + 1]
+///     }
+/// }
+/// ",
+///     apply_edits(src, &edits)
+/// );
+/// assert!(expand_define_dp("define_dp! {Dp}").is_err());
+/// ```
+pub fn expand_define_dp(source: &str) -> Result<Vec<Edit>, String> {
+    let tokens: Vec<Token> = tokenize(source)
+        .into_iter()
+        .filter(|t| !t.is_trivia())
+        .collect();
+    let defines_own = tokens
+        .windows(3)
+        .any(|w| w[0].is_ident("macro_rules") && w[1].is_punct('!') && w[2].is_ident("define_dp"));
+    if defines_own {
+        return Ok(Vec::new());
+    }
+
+    let mut result = Vec::new();
+    for i in 0..tokens.len() {
+        let is_invocation = tokens[i].is_ident("define_dp")
+            && tokens.get(i + 1).is_some_and(|t| t.is_punct('!'))
+            && tokens
+                .get(i + 2)
+                .is_some_and(|t| t.is_punct('(') || t.is_punct('[') || t.is_punct('{'))
+            && !(i > 0 && (tokens[i - 1].is_punct(':') || tokens[i - 1].is_ident("macro_rules")));
+        if !is_invocation {
+            continue;
+        }
+        let close = matching_close(&tokens, i + 2)
+            .ok_or_else(|| "unclosed `define_dp!` invocation".to_string())?;
+        let mut end = tokens[close].end();
+        if !tokens[i + 2].is_punct('{') && tokens.get(close + 1).is_some_and(|t| t.is_punct(';')) {
+            end = tokens[close + 1].end();
+        }
+        let text = define_dp(source, &tokens[i + 3..close]).map_err(|e| {
+            format!(
+                "{} in `{}`",
+                e,
+                &source[tokens[i].start..tokens[close].end()]
+            )
+        })?;
+        result.push(Edit {
+            start: tokens[i].start,
+            end,
+            text,
+        });
+    }
+    Ok(result)
+}
+
+/// The expansion of `define_dp!` with the given arguments, `Type, field: length, ...`.
+fn define_dp(source: &str, args: &[Token]) -> Result<String, String> {
+    let text = |tokens: &[Token]| match (tokens.first(), tokens.last()) {
+        (Some(first), Some(last)) => source[first.start..last.end()].to_string(),
+        _ => String::new(),
+    };
+    let mut parts = split_commas(args).into_iter();
+    let ty = parts
+        .next()
+        .filter(|t| !t.is_empty())
+        .ok_or("missing type")?;
+    let mut fields = Vec::new();
+    for part in parts {
+        match part {
+            [] => continue,
+            [name, colon, len @ ..]
+                if name.kind == TokenKind::Ident
+                    && colon.is_punct(':')
+                    && !len.is_empty()
+                    && !len[0].is_punct(':') =>
+            {
+                // parentheses are only needed as an operand of `*`, not as an item of `vec!`
+                let factor = if is_atomic(len) {
+                    text(len)
+                } else {
+                    format!("({})", text(len))
+                };
+                fields.push((name.text, text(len), factor));
+            }
+            _ => return Err(format!("expected `field: length`, found `{}`", text(part))),
+        }
+    }
+    if fields.is_empty() {
+        return Err("expected at least one `field: length`".to_string());
+    }
+
+    let terms: Vec<String> = (0..fields.len())
+        .map(|i| {
+            std::iter::once(format!("self.{}", fields[i].0))
+                .chain(fields[i + 1..].iter().map(|(_, _, factor)| factor.clone()))
+                .collect::<Vec<_>>()
+                .join(" * ")
+        })
+        .collect();
+    // the same layout as the macro itself, which breaks the index onto lines from 4 fields on
+    let index = if terms.len() > 3 {
+        terms.join("\n            + ")
+    } else {
+        terms.join(" + ")
+    };
+    let sizes: Vec<&str> = fields.iter().map(|(_, len, _)| len.as_str()).collect();
+    Ok(format!(
+        "impl DPIndexer for {} {{
+    fn index(&self) -> usize {{
+        {}
+    }}
+    fn sizes() -> Vec<usize> {{
+        vec![{}]
+    }}
+}}",
+        text(ty),
+        index,
+        sizes.join(", ")
+    ))
+}
+
+/// Split tokens at the commas outside of any brackets.
+fn split_commas<'a, 'b>(tokens: &'b [Token<'a>]) -> Vec<&'b [Token<'a>]> {
+    let mut result = Vec::new();
+    let mut depth = 0i32;
+    let mut start = 0;
+    for (i, t) in tokens.iter().enumerate() {
+        if t.kind != TokenKind::Punct {
+            continue;
+        }
+        match t.text {
+            "(" | "[" | "{" | "<" => depth += 1,
+            ")" | "]" | "}" | ">" => depth -= 1,
+            "," if depth == 0 => {
+                result.push(&tokens[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    result.push(&tokens[start..]);
+    result
+}
+
+/// Whether an expression can be used as an operand of `*` without parentheses: a literal, a
+/// path, a field access, a call or an index, or something in parentheses already.
+fn is_atomic(tokens: &[Token]) -> bool {
+    if tokens.first().is_some_and(|t| t.is_punct('('))
+        && matching_close(tokens, 0) == Some(tokens.len() - 1)
+    {
+        return true;
+    }
+    let mut i = 0;
+    while i < tokens.len() {
+        let t = tokens[i];
+        if t.is_punct('(') || t.is_punct('[') {
+            if i == 0 {
+                return false;
+            }
+            match matching_close(tokens, i) {
+                Some(close) => i = close,
+                None => return false,
+            }
+        } else if (t.kind == TokenKind::Punct && !t.is_punct('.') && !t.is_punct(':'))
+            || t.is_ident("as")
+        {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
@@ -0,0 +1,385 @@
+//! The formatter behind the `rlfmt` binary: it flattens a solution file and the library
+//! modules it uses into a single source that can be pasted into the leetcode editor.
+
+mod cli;
+mod comments;
+mod deps;
+mod items;
+mod lexer;
+mod macros;
+mod prune;
+mod scaffold;
+mod source_map;
+mod strip;
+mod use_tree;
+mod verify;
+mod watch;
+mod workspace;
+
+pub use self::cli::*;
+pub use self::comments::*;
+pub use self::deps::*;
+pub use self::items::*;
+pub use self::lexer::*;
+pub use self::macros::*;
+pub use self::prune::*;
+pub use self::scaffold::*;
+pub use self::source_map::*;
+pub use self::strip::*;
+pub use self::use_tree::*;
+pub use self::verify::*;
+pub use self::watch::*;
+pub use self::workspace::*;
+
+use crate::{find_module, module_names, MODULES};
+use std::collections::HashSet;
+
+pub const HEADER: &str = "// These code are written and formatted by cauly-rust-leetcode-utils. See `https://github.com/CaulyKan/cauly-rust-leetcode-utils` for more infomation.\n";
+
+pub const LIB_CRATE: &str = "cauly_rust_leetcode_utils";
+
+/// Options of [`flatten`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// Only emit the library items that the solution can reach, see [`prune`].
+    pub prune: bool,
+    /// Library modules whose imports are kept as they are instead of being inlined. They are
+    /// still inlined when another inlined module depends on them.
+    pub not_inlined: Vec<&'static str>,
+    /// Keep `print!` and `println!` instead of commenting them out.
+    pub keep_prints: bool,
+    /// Keep the top level items marked `#[cfg(test)]` or `#[test]`.
+    pub keep_tests: bool,
+    /// Remove all comments except the header.
+    pub strip_comments: bool,
+    /// Remove comments, doc comments and blank lines from the inlined library code, see
+    /// [`minify`]. The solution is left as it is.
+    pub minify: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            prune: true,
+            not_inlined: Vec::new(),
+            keep_prints: false,
+            keep_tests: false,
+            strip_comments: false,
+            minify: false,
+        }
+    }
+}
+
+/// A range of the solution replaced by `text` when flattening.
+struct Replacement {
+    start: usize,
+    end: usize,
+    text: String,
+    /// A top level library import, the first of which is where the library code goes.
+    is_top_import: bool,
+}
+
+/// Flatten a solution: drop `struct Solution;`, the tests and debug output (see [`strip_edits`]),
+/// expand `define_dp!` (see [`expand_define_dp`]), and replace the imports of
+/// `cauly_rust_leetcode_utils` with the modules they need. Every module is inlined once, after
+/// the modules it depends on, at the place of the first top level import.
+pub fn flatten(content: &str, options: &Options) -> Result<String, String> {
+    flatten_mapped(content, options).map(|f| f.code)
+}
+
+/// Same as [`flatten`], but also tells where each line of the output comes from.
+pub fn flatten_mapped(content: &str, options: &Options) -> Result<Flattened, String> {
+    let mut edits = strip_edits(content, options.keep_tests, options.keep_prints);
+    edits.extend(expand_define_dp(content)?);
+    let mut replacements: Vec<Replacement> = merge_edits(edits)
+        .into_iter()
+        .map(|edit| Replacement {
+            start: edit.start,
+            end: edit.end,
+            text: edit.text,
+            is_top_import: false,
+        })
+        .collect();
+    let is_removed = |pos: usize| replacements.iter().any(|r| r.start <= pos && pos < r.end);
+    let statements: Vec<UseStatement> = find_use_statements(content)?
+        .into_iter()
+        .filter(|s| !is_removed(s.start))
+        .collect();
+    // the imports of the solution, which the inlined modules must not repeat
+    let mut seen_uses: HashSet<String> = statements
+        .iter()
+        .filter(|s| s.depth == 0 && !s.is_extern_crate)
+        .flat_map(|s| s.paths.iter().map(|p| p.to_use_statement()))
+        .collect();
+    let statements: Vec<UseStatement> = statements
+        .into_iter()
+        .filter(|s| s.paths.iter().any(is_library_path))
+        .collect();
+
+    let mut roots = Vec::new();
+    for statement in &statements {
+        let mut imports = Vec::new();
+        if statement.is_extern_crate {
+            if !options.not_inlined.is_empty() {
+                imports.push(content[statement.start..statement.end].to_string());
+            }
+        } else {
+            for path in &statement.paths {
+                if is_library_path(path) {
+                    imports.extend(library_import(
+                        path,
+                        statement.depth > 0,
+                        options,
+                        &mut roots,
+                    )?);
+                } else {
+                    imports.push(path.to_use_statement());
+                }
+            }
+        }
+        let text = imports.join(" ");
+        let (start, end) = if text.is_empty() {
+            whole_lines(content, statement.start, statement.end)
+        } else {
+            (statement.start, statement.end)
+        };
+        replacements.push(Replacement {
+            start,
+            end,
+            text,
+            is_top_import: statement.depth == 0,
+        });
+    }
+    replacements.sort_by_key(|r| r.start);
+
+    let solution = assemble(content, &replacements, None);
+    if roots.is_empty() {
+        return Ok(finish(solution, options));
+    }
+
+    let names = module_names();
+    let modules = resolve_dependencies(&roots, &names, |name| find_module(name).map(|m| m.source))?;
+    let modules: Vec<_> = modules.iter().filter_map(|&m| find_module(m)).collect();
+    let mut sources: Vec<String> = modules
+        .iter()
+        .map(|m| strip_crate_paths(m.source, &names))
+        .collect();
+    if options.prune {
+        sources = prune(&solution.code, &sources);
+    }
+    let mut inlined = Flattened::default();
+    inlined.push(HEADER, Origin::Generated);
+    for (module, source) in modules.iter().zip(sources) {
+        let source = dedup_uses(&source, &mut seen_uses);
+        let mut code = Flattened::default();
+        for (line, number) in source.lines().zip(trace_lines(module.source, &source)) {
+            code.push(&format!("{}\n", line), Origin::Library(module.name, number));
+        }
+        if options.minify {
+            code = minify(&code);
+        }
+        append(&mut inlined, code);
+    }
+    let position = replacements.iter().position(|r| r.is_top_import);
+    let result = assemble(content, &replacements, Some((inlined, position)));
+    Ok(finish(result, options))
+}
+
+/// The post-processing steps that work on the whole output.
+fn finish(flattened: Flattened, options: &Options) -> Flattened {
+    if options.strip_comments {
+        strip_comments(&flattened, |t| t.text != HEADER.trim_end())
+    } else {
+        flattened
+    }
+}
+
+/// Put the solution back together with its library imports replaced. `inlined` is the inlined
+/// library code, together with the index of the replacement it goes in front of.
+fn assemble(
+    content: &str,
+    replacements: &[Replacement],
+    inlined: Option<(Flattened, Option<usize>)>,
+) -> Flattened {
+    let mut result = Flattened::default();
+    let (mut inlined, position) = match inlined {
+        Some((code, position)) => (Some(code), position),
+        None => (None, None),
+    };
+    if position.is_none() {
+        if let Some(code) = inlined.take() {
+            append(&mut result, code);
+        }
+    }
+    let line_of = |pos: usize| content[..pos].matches('\n').count() + 1;
+    let mut pos = 0;
+    for (i, r) in replacements.iter().enumerate() {
+        solution_lines(&mut result, &content[pos..r.start], line_of(pos));
+        if position == Some(i) {
+            if let Some(code) = inlined.take() {
+                append(&mut result, code);
+            }
+        }
+        for text in r.text.split_inclusive('\n') {
+            // a multi-line replacement, i.e. an expanded macro, belongs to its first line
+            result.push(text, Origin::Solution(line_of(r.start)));
+        }
+        pos = r.end;
+    }
+    solution_lines(&mut result, &content[pos..], line_of(pos));
+    result
+}
+
+fn append(result: &mut Flattened, other: Flattened) {
+    let mut lines = other.code.split_inclusive('\n');
+    for origin in other.origins {
+        if let Some(line) = lines.next() {
+            result.push(line, origin);
+        }
+    }
+}
+
+fn is_library_path(path: &UsePath) -> bool {
+    path.segments
+        .iter()
+        .find(|s| !s.is_empty())
+        .map(|s| s.as_str())
+        == Some(LIB_CRATE)
+}
+
+/// Record the modules a library import needs into `roots`, and return the `use` statements or
+/// module shims that keep the names it introduces working once everything is inlined. The
+/// library code goes to the crate root, which imports at the top level see directly, while
+/// `nested` imports (e.g. in a local module) have to refer to it through `crate::`.
+fn library_import(
+    path: &UsePath,
+    nested: bool,
+    options: &Options,
+    roots: &mut Vec<&'static str>,
+) -> Result<Vec<String>, String> {
+    let rest: Vec<&str> = path
+        .segments
+        .iter()
+        .skip_while(|s| s.is_empty())
+        .skip(1)
+        .map(|s| s.as_str())
+        .collect();
+    let kept = || Ok(vec![path.to_use_statement()]);
+    if rest.is_empty() {
+        if path.is_glob {
+            roots.extend(
+                MODULES
+                    .iter()
+                    .map(|m| m.name)
+                    .filter(|m| !options.not_inlined.contains(m)),
+            );
+            if !options.not_inlined.is_empty() {
+                return kept();
+            }
+            if nested {
+                return Ok(vec!["use crate::*;".to_string()]);
+            }
+        }
+        return Ok(Vec::new());
+    }
+    // macros exported at the root of the crate
+    let root_item = format!("{}!", rest[0]);
+    if let Some(module) = MODULES
+        .iter()
+        .find(|m| m.items.contains(&root_item.as_str()))
+        .map(|m| m.name)
+    {
+        if options.not_inlined.contains(&module) {
+            return kept();
+        }
+        roots.push(module);
+        return Ok(Vec::new());
+    }
+    let module = find_module(rest[0]).map(|m| m.name).ok_or_else(|| {
+        format!(
+            "unknown module `{}` in `{}`",
+            rest[0],
+            path.to_use_statement()
+        )
+    })?;
+    if options.not_inlined.contains(&module) {
+        return kept();
+    }
+    roots.push(module);
+
+    let mut result = Vec::new();
+    let base = if nested { "crate" } else { "self" };
+    if rest.len() == 1 && !path.is_glob {
+        // the module itself is imported, e.g. `use cauly_rust_leetcode_utils::grid;`
+        let name = path.alias.as_deref().unwrap_or(module);
+        let root = if nested { "crate" } else { "super" };
+        result.push(format!("mod {} {{ pub use {}::*; }}", name, root));
+    } else if rest.len() == 1 {
+        if nested {
+            result.push("use crate::*;".to_string());
+        }
+    } else if nested || rest.len() > 2 || path.alias.is_some() {
+        // a renamed item, a nested path such as an enum variant, or any item of a nested import
+        let inner = UsePath {
+            segments: std::iter::once(base)
+                .chain(rest.iter().skip(1).cloned())
+                .map(|s| s.to_string())
+                .collect(),
+            alias: path.alias.clone(),
+            is_glob: path.is_glob,
+        };
+        if inner.alias.as_deref() != Some("_") {
+            result.push(inner.to_use_statement());
+        }
+    }
+    Ok(result)
+}
+
+/// Apply the line based rules to a piece of the solution itself, starting at line `line`.
+fn solution_lines(result: &mut Flattened, content: &str, line: usize) {
+    for (i, text) in content.split_inclusive('\n').enumerate() {
+        let origin = Origin::Solution(line + i);
+        if text.trim_start().starts_with("pub struct Solution;")
+            || text.trim_start().starts_with("struct Solution;")
+        {
+            // skip
+        } else {
+            result.push(text, origin);
+        }
+    }
+}
+
+/// Drop the paths of top-level single-line `use` statements that were already imported, since
+/// importing the same name twice is an error once modules are pasted next to each other.
+fn dedup_uses(source: &str, seen: &mut HashSet<String>) -> String {
+    let mut result = String::new();
+    for line in source.lines() {
+        if let Some(paths) = use_line_paths(line) {
+            let count = paths.len();
+            let new: Vec<UsePath> = paths
+                .into_iter()
+                .filter(|p| seen.insert(p.to_use_statement()))
+                .collect();
+            if new.is_empty() {
+                continue;
+            }
+            if new.len() < count {
+                result.push_str(&use_statements(&new));
+                result.push('\n');
+                continue;
+            }
+        }
+        result.push_str(line);
+        result.push('\n');
+    }
+    result
+}
+
+/// The paths of a top-level `use` statement on a single line.
+fn use_line_paths(line: &str) -> Option<Vec<UsePath>> {
+    if !line.starts_with("use ") || !line.trim_end().ends_with(';') {
+        return None;
+    }
+    let statements = find_use_statements(line).ok()?;
+    Some(statements.into_iter().flat_map(|s| s.paths).collect())
+}
@@ -0,0 +1,244 @@
+use super::deps::whole_lines;
+use super::items::{idents, parse_items, Item, ItemKind};
+use super::use_tree::{find_use_statements, use_statements, UsePath};
+use std::collections::HashSet;
+
+struct Node {
+    module: usize,
+    item: Item,
+    members: Vec<Item>,
+    kept: bool,
+    member_kept: Vec<bool>,
+}
+
+/// Remove the items of the inlined library modules that `solution` can't reach. Starting from
+/// the identifiers used by the solution, an item is kept when its name is used by kept code,
+/// an impl when its self type and trait are kept, and a method when its name is used. Methods
+/// of library traits are removed from the trait and all its impls together.
+///
+/// Returns the pruned modules; modules with nothing left are returned as empty strings.
+///
+/// # Example
+/// ```
+/// use cauly_rust_leetcode_utils::rlfmt::*;
+/// let lib = "// #region a\npub struct A;\n\npub struct B;\n\nimpl A {\n    pub fn f() -> B { B }\n\n    pub fn g() {}\n}\n// #endregion\n";
+/// assert_eq!(
+///     vec!["// #region a\npub struct A;\n\npub struct B;\n\nimpl A {\n    pub fn f() -> B { B }\n}\n// #endregion\n"],
+///     prune("fn main() { A::f(); }", &[lib.to_string()])
+/// );
+/// assert_eq!(vec![""], prune("fn main() {}", &[lib.to_string()]));
+/// ```
+pub fn prune(solution: &str, modules: &[String]) -> Vec<String> {
+    let mut nodes = Vec::new();
+    for (module, source) in modules.iter().enumerate() {
+        for item in parse_items(source, (0, source.len())) {
+            let members = match item.body {
+                Some(body) if item.kind != ItemKind::Mod => parse_items(source, body),
+                _ => Vec::new(),
+            };
+            nodes.push(Node {
+                module,
+                member_kept: vec![false; members.len()],
+                item,
+                members,
+                kept: false,
+            });
+        }
+    }
+    let defined: HashSet<String> = nodes
+        .iter()
+        .filter(|n| !matches!(n.item.kind, ItemKind::Impl | ItemKind::MacroCall))
+        .filter_map(|n| n.item.name.clone())
+        .collect();
+    let foreign_traits: Vec<bool> = nodes
+        .iter()
+        .map(|n| {
+            n.item.kind == ItemKind::Impl
+                && !n.item.impl_trait.is_empty()
+                && !n.item.impl_trait.iter().any(|t| defined.contains(t))
+        })
+        .collect();
+
+    let mut referenced: HashSet<String> = idents(solution, (0, solution.len()))
+        .into_iter()
+        .map(|s| s.to_string())
+        .collect();
+    let mut kept_names: HashSet<String> = HashSet::new();
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (i, node) in nodes.iter_mut().enumerate() {
+            let source = &modules[node.module];
+            if !node.kept {
+                let item = &node.item;
+                let keep = match item.kind {
+                    ItemKind::Use | ItemKind::ExternCrate => false,
+                    ItemKind::MacroCall | ItemKind::Other => true,
+                    ItemKind::Impl => item
+                        .impl_self
+                        .iter()
+                        .chain(item.impl_trait.iter())
+                        .filter(|name| defined.contains(*name))
+                        .all(|name| kept_names.contains(name)),
+                    ItemKind::Trait => {
+                        item.name.as_ref().is_some_and(|n| referenced.contains(n))
+                            || node.members.iter().any(|m| {
+                                m.kind == ItemKind::Fn
+                                    && m.name.as_ref().is_some_and(|n| referenced.contains(n))
+                            })
+                    }
+                    _ => item.name.as_ref().is_some_and(|n| referenced.contains(n)),
+                };
+                if keep {
+                    node.kept = true;
+                    changed = true;
+                    if let Some(name) = &item.name {
+                        kept_names.insert(name.clone());
+                    }
+                    let header_end = item.body.map_or(item.end, |b| b.0);
+                    referenced.extend(
+                        idents(source, (item.start, header_end))
+                            .into_iter()
+                            .map(|s| s.to_string()),
+                    );
+                }
+            }
+            if node.kept {
+                for (member, kept) in node.members.iter().zip(node.member_kept.iter_mut()) {
+                    if *kept {
+                        continue;
+                    }
+                    let keep = member.kind != ItemKind::Fn
+                        || foreign_traits[i]
+                        || member.name.as_ref().is_some_and(|n| referenced.contains(n));
+                    if keep {
+                        *kept = true;
+                        changed = true;
+                        referenced.extend(
+                            idents(source, (member.start, member.end))
+                                .into_iter()
+                                .map(|s| s.to_string()),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    let mut removed: Vec<Vec<Replacement>> = vec![Vec::new(); modules.len()];
+    let mut has_items = vec![false; modules.len()];
+    for node in &nodes {
+        let source = &modules[node.module];
+        let item = &node.item;
+        if item.kind == ItemKind::Use {
+            let statement = &source[item.start..item.end];
+            match needed_paths(statement, &referenced) {
+                Some(paths) if paths.is_empty() => {
+                    removed[node.module].push((item_lines(source, item.start, item.end), None));
+                }
+                Some(paths) => {
+                    // only some names of a grouped import are used
+                    let text = use_statements(&paths);
+                    removed[node.module].push(((item.start, item.end), Some(text)));
+                }
+                None => {}
+            }
+            continue;
+        }
+        let keep = match item.kind {
+            // an inherent impl whose methods are all unused
+            ItemKind::Impl if item.impl_trait.is_empty() && !node.members.is_empty() => {
+                node.member_kept.iter().any(|&k| k)
+            }
+            _ => node.kept,
+        };
+        if !keep {
+            removed[node.module].push((item_lines(source, item.start, item.end), None));
+            continue;
+        }
+        if !matches!(item.kind, ItemKind::ExternCrate | ItemKind::Other) {
+            has_items[node.module] = true;
+        }
+        for (member, &kept) in node.members.iter().zip(node.member_kept.iter()) {
+            if !kept {
+                removed[node.module].push((item_lines(source, member.start, member.end), None));
+            }
+        }
+    }
+
+    modules
+        .iter()
+        .enumerate()
+        .map(|(i, source)| {
+            if has_items[i] {
+                squash_blank_lines(&remove_ranges(source, &mut removed[i]))
+            } else {
+                String::new()
+            }
+        })
+        .collect()
+}
+
+/// The paths of a `use` statement that are used, or None if they all are. Globs are always
+/// used.
+fn needed_paths(statement: &str, referenced: &HashSet<String>) -> Option<Vec<UsePath>> {
+    let statements = find_use_statements(statement).ok()?;
+    let paths: Vec<&UsePath> = statements.iter().flat_map(|s| s.paths.iter()).collect();
+    let needed: Vec<UsePath> = paths
+        .iter()
+        .filter(|p| match p.name() {
+            Some(name) => referenced.contains(name),
+            None => true,
+        })
+        .map(|&p| p.clone())
+        .collect();
+    if needed.len() == paths.len() || (!needed.is_empty() && !statement.starts_with("use ")) {
+        None
+    } else {
+        Some(needed)
+    }
+}
+
+/// The whole lines of an item together with one blank line before it, if there is one.
+fn item_lines(source: &str, start: usize, end: usize) -> (usize, usize) {
+    let (start, end) = whole_lines(source, start, end);
+    if start > 0 && source.as_bytes()[start - 1] == b'\n' {
+        let previous = source[..start - 1].rfind('\n').map_or(0, |i| i + 1);
+        if source[previous..start].trim().is_empty() {
+            return (previous, end);
+        }
+    }
+    (start, end)
+}
+
+/// A range of a module to remove, or to replace with some text.
+type Replacement = ((usize, usize), Option<String>);
+
+fn remove_ranges(source: &str, ranges: &mut [Replacement]) -> String {
+    ranges.sort_unstable();
+    let mut result = String::new();
+    let mut pos = 0;
+    for ((start, end), text) in ranges.iter() {
+        if *start >= pos {
+            result.push_str(&source[pos..*start]);
+            result.push_str(text.as_deref().unwrap_or(""));
+            pos = *end;
+        }
+    }
+    result.push_str(&source[pos..]);
+    result
+}
+
+/// Collapse runs of blank lines left by removed items into a single one.
+fn squash_blank_lines(source: &str) -> String {
+    let mut result = String::new();
+    let mut last_blank = false;
+    for line in source.split_inclusive('\n') {
+        let blank = line.trim().is_empty();
+        if !(blank && last_blank) {
+            result.push_str(line);
+        }
+        last_blank = blank;
+    }
+    result
+}
@@ -0,0 +1,385 @@
+use super::items::{parse_items, ItemKind};
+use super::lexer::{tokenize, Token, TokenKind};
+use crate::binary_tree::{RefTreeNode, TreeNode};
+use crate::design::snake_case;
+use crate::leetcode_io::{parse_value, Value};
+
+/// One `Example n:` block of a problem description.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Example {
+    /// The `name = value` pairs after `Input:`.
+    pub inputs: Vec<(String, String)>,
+    /// The value after `Output:`.
+    pub output: String,
+}
+
+/// A method of `impl Solution`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Signature {
+    pub name: String,
+    pub params: Vec<(String, String)>,
+    /// None for methods returning `()`.
+    pub output: Option<String>,
+}
+
+/// Find the examples in the text of a problem description.
+///
+/// # Example
+/// ```
+/// use cauly_rust_leetcode_utils::rlfmt::*;
+/// let text = "Example 1:\n\nInput: nums = [2,7,11,15], target = 9\nOutput: [0,1]\nExplanation: Because nums[0] + nums[1] == 9, we return [0, 1].\n";
+/// let examples = parse_examples(text).unwrap();
+/// assert_eq!(vec![("nums".to_string(), "[2,7,11,15]".to_string()), ("target".to_string(), "9".to_string())], examples[0].inputs);
+/// assert_eq!("[0,1]", examples[0].output);
+/// ```
+pub fn parse_examples(text: &str) -> Result<Vec<Example>, String> {
+    let mut result = Vec::new();
+    let mut rest = text;
+    while let Some(i) = rest.find("Input:") {
+        rest = &rest[i + "Input:".len()..];
+        let output_at = rest
+            .find("Output:")
+            .ok_or_else(|| format!("example {} has no `Output:`", result.len() + 1))?;
+        let input = &rest[..output_at];
+        rest = &rest[output_at + "Output:".len()..];
+        let output = value_prefix(rest);
+        rest = &rest[output.len()..];
+
+        let mut inputs = Vec::new();
+        for part in split_top_level(input, ',') {
+            let part = part.trim();
+            if part.is_empty() {
+                continue;
+            }
+            let (name, value) = part
+                .split_once('=')
+                .ok_or_else(|| format!("expected `name = value` in input `{}`", part))?;
+            inputs.push((name.trim().to_string(), value.trim().to_string()));
+        }
+        result.push(Example {
+            inputs,
+            output: output.trim().to_string(),
+        });
+    }
+    if result.is_empty() {
+        return Err("no `Input:` found in the examples".to_string());
+    }
+    Ok(result)
+}
+
+/// The value at the start of `text`: everything up to the end of the line, continuing over
+/// line breaks inside brackets.
+fn value_prefix(text: &str) -> &str {
+    let mut depth = 0i32;
+    let mut in_string = false;
+    let mut started = false;
+    for (i, c) in text.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '[' | '(' if !in_string => depth += 1,
+            ']' | ')' if !in_string => depth -= 1,
+            '\n' if !in_string && depth <= 0 && started => return &text[..i],
+            _ => {}
+        }
+        started |= !c.is_whitespace();
+    }
+    text
+}
+
+/// Split `text` at `separator`, except inside brackets and string literals.
+fn split_top_level(text: &str, separator: char) -> Vec<&str> {
+    let mut result = Vec::new();
+    let mut depth = 0i32;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '[' | '(' | '{' | '<' => depth += 1,
+            ']' | ')' | '}' | '>' => depth -= 1,
+            _ if c == separator && depth == 0 => {
+                result.push(&text[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    result.push(&text[start..]);
+    result
+}
+
+/// Find the methods of `impl Solution` in a solution file.
+///
+/// # Example
+/// ```
+/// use cauly_rust_leetcode_utils::rlfmt::*;
+/// let src = "impl Solution {\n    pub fn two_sum(nums: Vec<i32>, target: i32) -> Vec<i32> {\n        vec![]\n    }\n}\n";
+/// let signatures = solution_signatures(src);
+/// assert_eq!("two_sum", signatures[0].name);
+/// assert_eq!(("target".to_string(), "i32".to_string()), signatures[0].params[1]);
+/// assert_eq!(Some("Vec<i32>".to_string()), signatures[0].output);
+/// ```
+pub fn solution_signatures(source: &str) -> Vec<Signature> {
+    let mut result = Vec::new();
+    for item in parse_items(source, (0, source.len())) {
+        if item.kind != ItemKind::Impl
+            || item.impl_self != ["Solution"]
+            || !item.impl_trait.is_empty()
+        {
+            continue;
+        }
+        let body = match item.body {
+            Some(body) => body,
+            None => continue,
+        };
+        for method in parse_items(source, body) {
+            if method.kind != ItemKind::Fn {
+                continue;
+            }
+            if let Some(signature) = parse_signature(&source[method.start..method.end]) {
+                result.push(signature);
+            }
+        }
+    }
+    result
+}
+
+fn parse_signature(text: &str) -> Option<Signature> {
+    let tokens: Vec<Token> = tokenize(text)
+        .into_iter()
+        .filter(|t| !t.is_trivia())
+        .collect();
+    let fn_at = tokens.iter().position(|t| t.is_ident("fn"))?;
+    let name = tokens.get(fn_at + 1)?.text.to_string();
+    let open = (fn_at..tokens.len()).find(|&i| tokens[i].is_punct('('))?;
+    let close = super::lexer::matching_close(&tokens, open)?;
+    let params_text = &text[tokens[open].end()..tokens[close].start];
+    let mut params = Vec::new();
+    for param in split_top_level(params_text, ',') {
+        let param = param.trim();
+        if param.is_empty() || param.ends_with("self") {
+            continue;
+        }
+        let (name, ty) = param.split_once(':')?;
+        let name = name.trim().trim_start_matches("mut ").trim();
+        params.push((name.to_string(), normalize_type(ty)));
+    }
+    let body =
+        (close..tokens.len()).find(|&i| tokens[i].is_punct('{') || tokens[i].is_ident("where"))?;
+    let output = if tokens.get(close + 1).is_some_and(|t| t.is_punct('-'))
+        && tokens.get(close + 2).is_some_and(|t| t.is_punct('>'))
+    {
+        Some(normalize_type(
+            &text[tokens[close + 2].end()..tokens[body].start],
+        ))
+    } else {
+        None
+    };
+    Some(Signature {
+        name,
+        params,
+        output,
+    })
+}
+
+/// Remove all whitespace but the one after `&mut`.
+fn normalize_type(ty: &str) -> String {
+    let compact: String = tokenize(ty.trim())
+        .into_iter()
+        .filter(|t| !t.is_trivia())
+        .map(|t| {
+            if t.kind == TokenKind::Ident && t.text == "mut" {
+                "mut "
+            } else {
+                t.text
+            }
+        })
+        .collect();
+    compact
+}
+
+/// The rust expression of a value from an example, for a parameter of type `ty`.
+///
+/// # Example
+/// ```
+/// use cauly_rust_leetcode_utils::rlfmt::*;
+/// assert_eq!(Ok("vec![vec!['1', '0'], vec!['0', '1']]".to_string()), literal("[[\"1\",\"0\"],[\"0\",\"1\"]]", "Vec<Vec<char>>"));
+/// assert_eq!(Ok("\"abc\".to_string()".to_string()), literal("\"abc\"", "String"));
+/// assert_eq!(Ok(r#"vec!["a\"b".to_string(), "c,d".to_string()]"#.to_string()), literal(r#"[ "a\"b", "c,d" ]"#, "Vec<String>"));
+/// assert_eq!(Ok("TreeNode::from_string(\"[1,null,2]\").unwrap()".to_string()), literal("[1, null, 2]", "Option<Rc<RefCell<TreeNode>>>"));
+/// assert_eq!(Ok("ListNode::from_vec(&[1, 2])".to_string()), literal("[1,2]", "Option<Box<ListNode>>"));
+/// assert_eq!(Ok("2.0".to_string()), literal("2", "f64"));
+/// assert!(literal("[1]", "i32").is_err());
+/// ```
+pub fn literal(value: &str, ty: &str) -> Result<String, String> {
+    let parsed =
+        parse_value(value).map_err(|e| format!("can't parse `{}`: {}", value.trim(), e))?;
+    value_literal(&parsed, &normalize_type(ty))
+}
+
+fn value_literal(value: &Value, ty: &str) -> Result<String, String> {
+    let mismatch = || format!("`{}` is not a `{}`", value, ty);
+    if let Some(inner) = ty.strip_prefix("&mut ").or_else(|| ty.strip_prefix('&')) {
+        return value_literal(value, inner);
+    }
+    match ty {
+        "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16" | "u32" | "u64" | "u128"
+        | "usize" => match value {
+            Value::Int(i) => Ok(i.to_string()),
+            _ => Err(mismatch()),
+        },
+        "f32" | "f64" => match value {
+            Value::Int(i) => Ok(format!("{}.0", i)),
+            Value::Float(x) => Ok(format!("{:?}", x)),
+            _ => Err(mismatch()),
+        },
+        "bool" => match value {
+            Value::Bool(b) => Ok(b.to_string()),
+            _ => Err(mismatch()),
+        },
+        "String" | "str" => match value {
+            Value::Str(s) if ty == "String" => Ok(format!("{:?}.to_string()", s)),
+            Value::Str(s) => Ok(format!("{:?}", s)),
+            _ => Err(mismatch()),
+        },
+        "char" => match value {
+            Value::Str(s) if s.chars().count() == 1 => {
+                Ok(format!("{:?}", s.chars().next().unwrap()))
+            }
+            _ => Err(mismatch()),
+        },
+        "Option<Rc<RefCell<TreeNode>>>" => {
+            let tree = match value {
+                Value::Null => None,
+                Value::List(_) => {
+                    TreeNode::from_string(&value.to_string()).map_err(|_| mismatch())?
+                }
+                _ => return Err(mismatch()),
+            };
+            if tree.is_none() {
+                return Ok("None".to_string());
+            }
+            Ok(format!(
+                "TreeNode::from_string(\"{}\").unwrap()",
+                tree.to_leetcode_string()
+            ))
+        }
+        "Option<Box<ListNode>>" => {
+            let items = list_items(value).ok_or_else(mismatch)?;
+            let items = items
+                .iter()
+                .map(|item| value_literal(item, "i32"))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(format!("ListNode::from_vec(&[{}])", items.join(", ")))
+        }
+        _ => {
+            if let Some(inner) = ty.strip_prefix("Vec<").and_then(|t| t.strip_suffix('>')) {
+                let items = list_items(value).ok_or_else(mismatch)?;
+                let items = items
+                    .iter()
+                    .map(|item| value_literal(item, inner))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(format!("vec![{}]", items.join(", ")))
+            } else if let Some(inner) = ty.strip_prefix("Option<").and_then(|t| t.strip_suffix('>'))
+            {
+                match value {
+                    Value::Null => Ok("None".to_string()),
+                    _ => Ok(format!("Some({})", value_literal(value, inner)?)),
+                }
+            } else {
+                Err(format!("type `{}` is not supported", ty))
+            }
+        }
+    }
+}
+
+/// The items of a list, or None if `value` isn't one.
+fn list_items(value: &Value) -> Option<&[Value]> {
+    match value {
+        Value::List(items) => Some(items),
+        _ => None,
+    }
+}
+
+/// Generate a test module with one `#[test]` function per example, calling the method of
+/// `signatures` whose parameters match the example inputs.
+pub fn scaffold(examples: &[Example], signatures: &[Signature]) -> Result<String, String> {
+    let mut result = String::from("#[cfg(test)]\nmod tests {\n    use super::*;\n");
+    for (i, example) in examples.iter().enumerate() {
+        let names: Vec<String> = example.inputs.iter().map(|(n, _)| snake_case(n)).collect();
+        let signature = signatures
+            .iter()
+            .find(|s| s.params.iter().map(|(n, _)| n).eq(names.iter()))
+            .or_else(|| {
+                signatures
+                    .iter()
+                    .find(|s| s.params.len() == example.inputs.len())
+            })
+            .ok_or_else(|| {
+                format!(
+                    "no method of `impl Solution` takes the inputs of example {}: {}",
+                    i + 1,
+                    names.join(", ")
+                )
+            })?;
+        let context = |e: String| format!("example {}: {}", i + 1, e);
+
+        result.push_str(&format!("\n    #[test]\n    fn test{}() {{\n", i + 1));
+        let mut args = Vec::new();
+        let mut mutated = None;
+        for ((name, ty), (_, value)) in signature.params.iter().zip(&example.inputs) {
+            let value = literal(value, ty).map_err(context)?;
+            if ty.starts_with("&mut ") {
+                result.push_str(&format!("        let mut {} = {};\n", name, value));
+                args.push(format!("&mut {}", name));
+                mutated.get_or_insert((name, ty));
+            } else if ty.starts_with('&') {
+                result.push_str(&format!("        let {} = {};\n", name, value));
+                args.push(format!("&{}", name));
+            } else {
+                result.push_str(&format!("        let {} = {};\n", name, value));
+                args.push(name.clone());
+            }
+        }
+        let call = format!("Solution::{}({})", signature.name, args.join(", "));
+        match (&signature.output, mutated) {
+            (Some(ty), _) => {
+                let expected = literal(&example.output, ty).map_err(context)?;
+                result.push_str(&assertion(&expected, &call, ty));
+            }
+            (None, Some((name, ty))) => {
+                // a method changing its argument in place, e.g. `fn rotate(nums: &mut Vec<i32>)`
+                let expected = literal(&example.output, ty).map_err(context)?;
+                result.push_str(&format!("        {};\n", call));
+                result.push_str(&assertion(&expected, name, ty));
+            }
+            (None, None) => result.push_str(&format!("        {};\n", call)),
+        }
+        result.push_str("    }\n");
+    }
+    result.push_str("}\n");
+    Ok(result)
+}
+
+/// The line checking that `actual` is `expected`. LeetCode rounds floats to 5 decimals, so
+/// they only need to be that close.
+fn assertion(expected: &str, actual: &str, ty: &str) -> String {
+    let ty = normalize_type(ty);
+    match ty.trim_start_matches("&mut ").trim_start_matches('&') {
+        "f32" | "f64" => format!(
+            "        assert!(({} - {}).abs() < 1e-5);\n",
+            expected, actual
+        ),
+        _ => format!("        assert_eq!({}, {});\n", expected, actual),
+    }
+}
@@ -0,0 +1,85 @@
+/// Where a line of the flattened output comes from. Line numbers are 1-based.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Origin {
+    /// A line of the solution file.
+    Solution(usize),
+    /// A line of an inlined library module; None if it can't be traced back to one line.
+    Library(&'static str, Option<usize>),
+    /// A line of a local module file that the solution declares with `mod foo;`.
+    File(String, usize),
+    /// Lines added by rlfmt itself, such as the header.
+    Generated,
+}
+
+/// The result of flattening: the code, and the origin of each of its lines.
+#[derive(Debug, Default, Clone)]
+pub struct Flattened {
+    pub code: String,
+    pub origins: Vec<Origin>,
+}
+
+impl Flattened {
+    /// Append `text`. Every line starting inside `text` is attributed to `origin`, with the line
+    /// number counting up from the one given.
+    pub fn push(&mut self, text: &str, origin: Origin) {
+        let mut origin = origin;
+        for line in text.split_inclusive('\n') {
+            if self.code.is_empty() || self.code.ends_with('\n') {
+                self.origins.push(origin.clone());
+            }
+            self.code.push_str(line);
+            origin = match origin {
+                Origin::Solution(n) => Origin::Solution(n + 1),
+                Origin::Library(m, Some(n)) => Origin::Library(m, Some(n + 1)),
+                Origin::File(f, n) => Origin::File(f, n + 1),
+                o => o,
+            };
+        }
+    }
+
+    /// Trace the lines of the solution back through `earlier`, the result of an earlier step
+    /// that produced the solution this was flattened from.
+    pub fn compose(self, earlier: &Flattened) -> Flattened {
+        let origins = self
+            .origins
+            .into_iter()
+            .map(|origin| match origin {
+                Origin::Solution(n) => earlier.origin(n).cloned().unwrap_or(Origin::Generated),
+                o => o,
+            })
+            .collect();
+        Flattened {
+            code: self.code,
+            origins,
+        }
+    }
+
+    /// The origin of a 1-based line of the flattened code.
+    pub fn origin(&self, line: usize) -> Option<&Origin> {
+        if line == 0 {
+            None
+        } else {
+            self.origins.get(line - 1)
+        }
+    }
+}
+
+/// Trace each line of `transformed` back to a line of `original`, for code that only had lines
+/// removed or edited in place. Lines are matched in order by their trimmed text.
+pub fn trace_lines(original: &str, transformed: &str) -> Vec<Option<usize>> {
+    let original: Vec<&str> = original.lines().map(|l| l.trim()).collect();
+    let mut next = 0;
+    transformed
+        .lines()
+        .map(|line| {
+            let line = line.trim();
+            match original[next..].iter().position(|&l| l == line) {
+                Some(i) => {
+                    next += i + 1;
+                    Some(next)
+                }
+                None => None,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,166 @@
+use super::deps::whole_lines;
+use super::items::{parse_items, ItemKind};
+use super::lexer::{matching_close, tokenize, Token};
+
+/// A change to a source: `start..end` is replaced by `text`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Edit {
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+}
+
+/// Find the test code and the debugging output in a solution:
+/// * items marked `#[cfg(test)]` or `#[test]`, at any depth of modules, impls and traits, are
+///   removed unless `keep_tests` is set;
+/// * `dbg!(x)` becomes `(x)`, or is removed when it is a statement of its own;
+/// * `eprint!`, `eprintln!`, and `print!`, `println!` are removed, or replaced by `()` when
+///   used as an expression. A print statement alone on its line is commented out instead.
+///   All of them are kept if `keep_prints` is set.
+///
+/// The edits are sorted and don't overlap.
+///
+/// # Example
+/// ```
+/// use cauly_rust_leetcode_utils::rlfmt::*;
+/// let src = "fn f(x: i32) -> i32 {\n    eprintln!(\"{}\", x);\n    let y = dbg!(x + 1);\n    println!(\"{}\", y);\n    y\n}\n#[test]\nfn test1() {}\n";
+/// let edits = strip_edits(src, false, false);
+/// assert_eq!(
+///     "fn f(x: i32) -> i32 {\n    let y = (x + 1);\n//    println!(\"{}\", y);\n    y\n}\n",
+///     apply_edits(src, &edits)
+/// );
+/// ```
+pub fn strip_edits(source: &str, keep_tests: bool, keep_prints: bool) -> Vec<Edit> {
+    let mut edits = Vec::new();
+    if !keep_tests {
+        test_items(source, (0, source.len()), &mut edits);
+    }
+    if !keep_prints {
+        macro_edits(source, &mut edits);
+    }
+    merge_edits(edits)
+}
+
+/// Sort edits and drop the ones overlapping an earlier or larger edit, e.g. a `dbg!` inside a
+/// removed test.
+pub fn merge_edits(mut edits: Vec<Edit>) -> Vec<Edit> {
+    edits.sort_by_key(|e| (e.start, std::cmp::Reverse(e.end)));
+    let mut result: Vec<Edit> = Vec::new();
+    for edit in edits {
+        let overlaps = match result.last() {
+            Some(last) => edit.start < last.end,
+            None => false,
+        };
+        if !overlaps {
+            result.push(edit);
+        }
+    }
+    result
+}
+
+/// Apply sorted, non-overlapping edits to `source`.
+pub fn apply_edits(source: &str, edits: &[Edit]) -> String {
+    let mut result = String::new();
+    let mut pos = 0;
+    for edit in edits {
+        result.push_str(&source[pos..edit.start]);
+        result.push_str(&edit.text);
+        pos = edit.end;
+    }
+    result.push_str(&source[pos..]);
+    result
+}
+
+pub(super) fn is_test_attr(attr: &str) -> bool {
+    let attr: String = attr.chars().filter(|c| !c.is_whitespace()).collect();
+    attr == "#[cfg(test)]" || attr == "#[test]"
+}
+
+fn test_items(source: &str, range: (usize, usize), edits: &mut Vec<Edit>) {
+    for item in parse_items(source, range) {
+        if item.attrs.iter().any(|a| is_test_attr(a)) {
+            let (start, end) = whole_lines(source, item.start, item.end);
+            edits.push(Edit {
+                start,
+                end,
+                text: String::new(),
+            });
+        } else if let (ItemKind::Mod | ItemKind::Impl | ItemKind::Trait, Some(body)) =
+            (item.kind, item.body)
+        {
+            test_items(source, body, edits);
+        }
+    }
+}
+
+fn macro_edits(source: &str, edits: &mut Vec<Edit>) {
+    let tokens: Vec<Token> = tokenize(source)
+        .into_iter()
+        .filter(|t| !t.is_trivia())
+        .collect();
+    for i in 0..tokens.len() {
+        let name = tokens[i].text;
+        let is_call = ["dbg", "eprint", "eprintln", "print", "println"]
+            .iter()
+            .any(|&m| tokens[i].is_ident(m))
+            && tokens.get(i + 1).is_some_and(|t| t.is_punct('!'))
+            && tokens
+                .get(i + 2)
+                .is_some_and(|t| t.is_punct('(') || t.is_punct('[') || t.is_punct('{'))
+            && !(i > 0 && tokens[i - 1].is_punct(':'));
+        if !is_call {
+            continue;
+        }
+        let close = match matching_close(&tokens, i + 2) {
+            Some(close) => close,
+            None => continue,
+        };
+        let start = tokens[i].start;
+        let starts_statement = i == 0 || ["{", "}", ";"].iter().any(|p| tokens[i - 1].text == *p);
+        let semicolon = tokens.get(close + 1).filter(|t| t.is_punct(';'));
+        match semicolon {
+            Some(semicolon) if starts_statement => {
+                let end = semicolon.end();
+                let (line_start, line_end) = whole_lines(source, start, end);
+                // also at the end of a source without a final newline
+                let before = &source[source[..start].rfind('\n').map_or(0, |i| i + 1)..start];
+                let after = source[end..].split('\n').next().unwrap_or_default();
+                let is_own_line = before.trim().is_empty()
+                    && after.trim().is_empty()
+                    && !source[start..end].contains('\n');
+                if name.starts_with("print") && is_own_line {
+                    // keep the output visible for whoever reads the submission
+                    edits.push(Edit {
+                        start: line_start,
+                        end: line_start,
+                        text: "//".to_string(),
+                    });
+                } else {
+                    edits.push(Edit {
+                        start: line_start,
+                        end: line_end,
+                        text: String::new(),
+                    });
+                }
+            }
+            _ if name == "dbg" => {
+                // `dbg!` returns its arguments: `dbg!(a)` is `(a)`, `dbg!(a, b)` is `(a, b)`
+                edits.push(Edit {
+                    start,
+                    end: tokens[i + 2].end(),
+                    text: "(".to_string(),
+                });
+                edits.push(Edit {
+                    start: tokens[close].start,
+                    end: tokens[close].end(),
+                    text: ")".to_string(),
+                });
+            }
+            _ => edits.push(Edit {
+                start,
+                end: tokens[close].end(),
+                text: "()".to_string(),
+            }),
+        }
+    }
+}
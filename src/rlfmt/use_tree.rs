@@ -0,0 +1,287 @@
+use super::lexer::{Token, TokenKind};
+
+/// One leaf of a `use` tree, e.g. `a::b::C as D` out of `use a::{b::C as D, e::*};`.
+/// A path starting with `::` has an empty first segment, and a trailing `self` is removed,
+/// so `a::{self as x}` becomes `a as x`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct UsePath {
+    pub segments: Vec<String>,
+    pub alias: Option<String>,
+    pub is_glob: bool,
+}
+
+impl UsePath {
+    /// The last segment, or the alias if the path is renamed.
+    pub fn name(&self) -> Option<&str> {
+        if self.is_glob {
+            return None;
+        }
+        match &self.alias {
+            Some(alias) => Some(alias),
+            None => self.segments.last().map(|s| s.as_str()),
+        }
+    }
+
+    /// Turn this path back into a standalone `use` statement.
+    ///
+    /// # Example
+    /// ```
+    /// use cauly_rust_leetcode_utils::rlfmt::*;
+    /// let paths = parse_use_tree("std::{rc::Rc as R, collections::*}").unwrap();
+    /// assert_eq!("use std::rc::Rc as R;", paths[0].to_use_statement());
+    /// assert_eq!("use std::collections::*;", paths[1].to_use_statement());
+    /// ```
+    pub fn to_use_statement(&self) -> String {
+        let mut result = format!("use {}", self.segments.join("::"));
+        if self.is_glob {
+            result.push_str("::*");
+        }
+        if let Some(alias) = &self.alias {
+            result.push_str(" as ");
+            result.push_str(alias);
+        }
+        result.push(';');
+        result
+    }
+}
+
+/// Turn paths back into `use` statements on a single line. Paths with the same parent are
+/// grouped into one statement.
+///
+/// # Example
+/// ```
+/// use cauly_rust_leetcode_utils::rlfmt::*;
+/// let paths = parse_use_tree("std::collections::{HashMap, HashSet as Set}").unwrap();
+/// assert_eq!("use std::collections::{HashMap, HashSet as Set};", use_statements(&paths));
+/// let paths = parse_use_tree("std::{rc::Rc, cell::RefCell}").unwrap();
+/// assert_eq!("use std::rc::Rc; use std::cell::RefCell;", use_statements(&paths));
+/// ```
+pub fn use_statements(paths: &[UsePath]) -> String {
+    let parent = |p: &UsePath| -> Option<Vec<String>> {
+        if p.is_glob || p.segments.len() < 2 {
+            None
+        } else {
+            Some(p.segments[..p.segments.len() - 1].to_vec())
+        }
+    };
+    let first = paths.first().and_then(parent);
+    if paths.len() < 2 || first.is_none() || paths.iter().any(|p| parent(p) != first) {
+        let statements: Vec<String> = paths.iter().map(|p| p.to_use_statement()).collect();
+        return statements.join(" ");
+    }
+    let names: Vec<String> = paths
+        .iter()
+        .map(|p| {
+            let name = p.segments.last().unwrap();
+            match &p.alias {
+                Some(alias) => format!("{} as {}", name, alias),
+                None => name.clone(),
+            }
+        })
+        .collect();
+    format!(
+        "use {}::{{{}}};",
+        first.unwrap().join("::"),
+        names.join(", ")
+    )
+}
+
+/// A `use` or `extern crate` statement found in a source file. `start..end` is its byte
+/// range, including leading attributes and visibility and the closing `;`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct UseStatement {
+    pub start: usize,
+    pub end: usize,
+    /// How many `{}` blocks the statement is nested in, 0 for the top level of a file.
+    pub depth: usize,
+    pub is_extern_crate: bool,
+    pub paths: Vec<UsePath>,
+}
+
+/// Parse a use tree, i.e. the part of a `use` statement between `use` and `;`.
+///
+/// # Example
+/// ```
+/// use cauly_rust_leetcode_utils::rlfmt::*;
+/// let paths = parse_use_tree("a::{b::{self, C}, d::*}").unwrap();
+/// assert_eq!(3, paths.len());
+/// assert_eq!(vec!["a", "b"], paths[0].segments);
+/// assert_eq!(vec!["a", "b", "C"], paths[1].segments);
+/// assert_eq!((vec!["a".to_string(), "d".to_string()], true), (paths[2].segments.clone(), paths[2].is_glob));
+/// assert!(parse_use_tree("a::{b").is_err());
+/// ```
+pub fn parse_use_tree(tree: &str) -> Result<Vec<UsePath>, String> {
+    let tokens: Vec<Token> = super::lexer::tokenize(tree)
+        .into_iter()
+        .filter(|t| !t.is_trivia())
+        .collect();
+    let mut parser = UseTreeParser {
+        tokens: &tokens,
+        pos: 0,
+        result: Vec::new(),
+    };
+    parser.tree(Vec::new())?;
+    if parser.pos != tokens.len() {
+        return Err(format!(
+            "unexpected `{}` in use tree `{}`",
+            tokens[parser.pos].text,
+            tree.trim()
+        ));
+    }
+    Ok(parser.result)
+}
+
+struct UseTreeParser<'a, 'b> {
+    tokens: &'b [Token<'a>],
+    pos: usize,
+    result: Vec<UsePath>,
+}
+
+impl<'a, 'b> UseTreeParser<'a, 'b> {
+    fn peek(&self) -> Option<&Token<'a>> {
+        self.tokens.get(self.pos)
+    }
+
+    fn eat_path_sep(&mut self) -> bool {
+        match (self.tokens.get(self.pos), self.tokens.get(self.pos + 1)) {
+            (Some(a), Some(b)) if a.is_punct(':') && b.is_punct(':') => {
+                self.pos += 2;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn unexpected(&self) -> String {
+        match self.peek() {
+            Some(t) => format!("unexpected `{}` in use tree", t.text),
+            None => "unexpected end of use tree".to_string(),
+        }
+    }
+
+    fn tree(&mut self, mut prefix: Vec<String>) -> Result<(), String> {
+        if prefix.is_empty() && self.eat_path_sep() {
+            prefix.push(String::new());
+        }
+        loop {
+            let token = *self.peek().ok_or_else(|| self.unexpected())?;
+            if token.is_punct('{') {
+                self.pos += 1;
+                while !self.peek().is_some_and(|t| t.is_punct('}')) {
+                    self.tree(prefix.clone())?;
+                    if self.peek().is_some_and(|t| t.is_punct(',')) {
+                        self.pos += 1;
+                    } else if !self.peek().is_some_and(|t| t.is_punct('}')) {
+                        return Err(self.unexpected());
+                    }
+                }
+                self.pos += 1;
+                return Ok(());
+            } else if token.is_punct('*') {
+                self.pos += 1;
+                self.result.push(UsePath {
+                    segments: prefix,
+                    alias: None,
+                    is_glob: true,
+                });
+                return Ok(());
+            } else if token.kind == TokenKind::Ident {
+                self.pos += 1;
+                prefix.push(token.text.to_string());
+                if self.eat_path_sep() {
+                    continue;
+                }
+                let mut alias = None;
+                if self.peek().is_some_and(|t| t.is_ident("as")) {
+                    self.pos += 1;
+                    match self.peek() {
+                        Some(t) if t.kind == TokenKind::Ident => alias = Some(t.text.to_string()),
+                        _ => return Err(self.unexpected()),
+                    }
+                    self.pos += 1;
+                }
+                if prefix.len() > 1 && prefix.last().is_some_and(|s| s == "self") {
+                    prefix.pop();
+                }
+                self.result.push(UsePath {
+                    segments: prefix,
+                    alias,
+                    is_glob: false,
+                });
+                return Ok(());
+            } else {
+                return Err(self.unexpected());
+            }
+        }
+    }
+}
+
+/// Find all `use` and `extern crate` statements in `source`.
+///
+/// # Example
+/// ```
+/// use cauly_rust_leetcode_utils::rlfmt::*;
+/// let src = "use a::b;\nfn f() {\n    #[allow(unused)]\n    pub use c::{\n        d,\n    };\n}\nextern crate e;";
+/// let statements = find_use_statements(src).unwrap();
+/// assert_eq!("use a::b;", &src[statements[0].start..statements[0].end]);
+/// assert_eq!(1, statements[1].depth);
+/// assert!(src[statements[1].start..].starts_with("#[allow(unused)]"));
+/// assert_eq!(vec!["c", "d"], statements[1].paths[0].segments);
+/// assert!(statements[2].is_extern_crate);
+/// ```
+pub fn find_use_statements(source: &str) -> Result<Vec<UseStatement>, String> {
+    let tokens: Vec<Token> = super::lexer::tokenize(source)
+        .into_iter()
+        .filter(|t| !t.is_trivia())
+        .collect();
+    let mut result = Vec::new();
+    let mut depth = 0;
+    let mut item_start = 0;
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = tokens[i];
+        let is_use = token.is_ident("use");
+        let is_extern_crate =
+            token.is_ident("extern") && tokens.get(i + 1).is_some_and(|t| t.is_ident("crate"));
+        if is_use || is_extern_crate {
+            let end = (i..tokens.len())
+                .find(|&j| tokens[j].is_punct(';'))
+                .ok_or_else(|| {
+                    let line = source[token.start..].lines().next().unwrap_or_default();
+                    format!("missing `;` after `{}`", line)
+                })?;
+            let has_prefix = (tokens[item_start].is_punct('#')
+                && tokens.get(item_start + 1).is_some_and(|t| t.is_punct('[')))
+                || tokens[item_start].is_ident("pub");
+            let start = if has_prefix {
+                tokens[item_start].start
+            } else {
+                token.start
+            };
+            let tree_start = tokens[if is_use { i + 1 } else { i + 2 }].start;
+            let tree = &source[tree_start.min(tokens[end].start)..tokens[end].start];
+            // `extern crate a as b;` has the same shape as a use tree
+            let paths = parse_use_tree(tree)?;
+            result.push(UseStatement {
+                start,
+                end: tokens[end].end(),
+                depth,
+                is_extern_crate,
+                paths,
+            });
+            i = end + 1;
+            item_start = i;
+            continue;
+        }
+        if token.is_punct('{') {
+            depth += 1;
+        } else if token.is_punct('}') {
+            depth = depth.saturating_sub(1);
+        }
+        if token.is_punct('{') || token.is_punct('}') || token.is_punct(';') {
+            item_start = i + 1;
+        }
+        i += 1;
+    }
+    Ok(result)
+}
@@ -0,0 +1,188 @@
+use super::items::parse_items;
+use super::source_map::{Flattened, Origin};
+use std::fs;
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static VERIFY_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// The definitions leetcode provides to rust submissions, as found in the comments of its
+/// editor. Types already defined by the flattened code are left out.
+const PRELUDE: &[(&str, &str)] = &[
+    ("Solution", "pub struct Solution;\n"),
+    (
+        "ListNode",
+        "#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct ListNode {
+    pub val: i32,
+    pub next: Option<Box<ListNode>>,
+}
+
+impl ListNode {
+    #[inline]
+    fn new(val: i32) -> Self {
+        ListNode { next: None, val }
+    }
+}
+",
+    ),
+    (
+        "TreeNode",
+        "#[derive(Debug, PartialEq, Eq)]
+pub struct TreeNode {
+    pub val: i32,
+    pub left: Option<std::rc::Rc<std::cell::RefCell<TreeNode>>>,
+    pub right: Option<std::rc::Rc<std::cell::RefCell<TreeNode>>>,
+}
+
+impl TreeNode {
+    #[inline]
+    pub fn new(val: i32) -> Self {
+        TreeNode {
+            val,
+            left: None,
+            right: None,
+        }
+    }
+}
+",
+    ),
+];
+
+/// A compiler message, with its location mapped back to the file it came from.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Diagnostic {
+    /// `error` or `warning`.
+    pub level: String,
+    /// The error code, e.g. `E0599`.
+    pub code: Option<String>,
+    /// e.g. `src/lib.rs:12:5` or `binary_tree.rs:40:9 (inlined)`.
+    pub location: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match &self.code {
+            Some(code) => write!(
+                f,
+                "{}: {}[{}]: {}",
+                self.location, self.level, code, self.message
+            ),
+            None => write!(f, "{}: {}: {}", self.location, self.level, self.message),
+        }
+    }
+}
+
+/// The code that [`verify`] compiles: a leetcode like prelude followed by the flattened code.
+/// Returns the code and the number of prelude lines.
+pub fn verification_source(flattened: &Flattened) -> (String, usize) {
+    let defined: Vec<String> = parse_items(&flattened.code, (0, flattened.code.len()))
+        .into_iter()
+        .filter_map(|item| item.name)
+        .collect();
+    let mut prelude = String::new();
+    for (name, code) in PRELUDE {
+        if !defined.iter().any(|d| d == name) {
+            prelude.push_str(code);
+        }
+    }
+    let lines = prelude.lines().count();
+    (prelude + &flattened.code, lines)
+}
+
+/// Compile the flattened code with the local `rustc` (or `$RUSTC`) the way leetcode would, and
+/// report the errors and warnings at their place in `solution_path` or the library modules.
+/// Fails only if the compiler can't be run.
+pub fn verify(flattened: &Flattened, solution_path: &str) -> Result<Vec<Diagnostic>, String> {
+    let dir = std::env::temp_dir().join(format!(
+        "rlfmt-verify-{}-{}",
+        std::process::id(),
+        VERIFY_COUNT.fetch_add(1, Ordering::SeqCst)
+    ));
+    fs::create_dir_all(&dir).map_err(|e| format!("cannot create {}: {}", dir.display(), e))?;
+    let file = dir.join("lib.rs");
+    let (source, prelude_lines) = verification_source(flattened);
+    fs::write(&file, source).map_err(|e| format!("cannot write {}: {}", file.display(), e))?;
+
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let output = Command::new(&rustc)
+        .args([
+            "--edition",
+            "2021",
+            "--crate-type",
+            "lib",
+            "--emit=metadata",
+        ])
+        .args(["--error-format=short", "--crate-name", "solution", "-o"])
+        .arg(dir.join("solution.rmeta"))
+        .arg(&file)
+        .output();
+    let _ = fs::remove_dir_all(&dir);
+    let output = output.map_err(|e| format!("cannot run {}: {}", rustc, e))?;
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let file_prefix = format!("{}:", file.display());
+    let mut result = Vec::new();
+    for line in stderr.lines() {
+        let rest = match line.strip_prefix(&file_prefix) {
+            Some(rest) => rest,
+            None => {
+                if line.starts_with("error") && !line.starts_with("error: aborting") {
+                    result.push(Diagnostic {
+                        level: "error".to_string(),
+                        code: None,
+                        location: solution_path.to_string(),
+                        message: line.trim_start_matches("error: ").to_string(),
+                    });
+                }
+                continue;
+            }
+        };
+        // rest is `line:column: level: message`
+        let mut parts = rest.splitn(3, ':');
+        let (line_no, column, message) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(l), Some(c), Some(m)) => (l.parse::<usize>().unwrap_or(0), c, m.trim()),
+            _ => continue,
+        };
+        if line_no <= prelude_lines {
+            // about the prelude, e.g. the unused `ListNode::new`
+            continue;
+        }
+        let (level, message) = match message.find(": ") {
+            Some(i) => (&message[..i], &message[i + 2..]),
+            None => ("error", message),
+        };
+        let (level, code) = match level.find('[') {
+            Some(i) => (
+                &level[..i],
+                Some(level[i + 1..].trim_end_matches(']').to_string()),
+            ),
+            None => (level, None),
+        };
+        let location = match flattened.origin(line_no - prelude_lines) {
+            Some(Origin::Solution(n)) => format!("{}:{}:{}", solution_path, n, column),
+            Some(Origin::Library(module, Some(n))) => {
+                format!("{}.rs:{}:{} (inlined)", module, n, column)
+            }
+            Some(Origin::Library(module, None)) => format!("{}.rs (inlined)", module),
+            Some(Origin::File(file, n)) => format!("{}:{}:{}", file, n, column),
+            _ => format!("<flattened>:{}:{}", line_no - prelude_lines, column),
+        };
+        result.push(Diagnostic {
+            level: level.to_string(),
+            code,
+            location,
+            message: message.to_string(),
+        });
+    }
+    if !output.status.success() && !result.iter().any(|d| d.level == "error") {
+        result.push(Diagnostic {
+            level: "error".to_string(),
+            code: None,
+            location: solution_path.to_string(),
+            message: format!("{} failed: {}", rustc, stderr.trim()),
+        });
+    }
+    Ok(result)
+}
@@ -0,0 +1,103 @@
+use super::source_map::{Flattened, Origin};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Polls a set of files for changes, comparing their modification time and size.
+#[derive(Debug, Clone)]
+pub struct Watcher {
+    files: Vec<(PathBuf, Option<(SystemTime, u64)>)>,
+}
+
+impl Watcher {
+    pub fn new(files: Vec<PathBuf>) -> Self {
+        Watcher {
+            files: files
+                .into_iter()
+                .map(|f| {
+                    let stamp = stamp(&f);
+                    (f, stamp)
+                })
+                .collect(),
+        }
+    }
+
+    /// The files that were changed, created or deleted since the last call.
+    pub fn changed(&mut self) -> Vec<PathBuf> {
+        let mut result = Vec::new();
+        for (file, old) in self.files.iter_mut() {
+            let new = stamp(file);
+            if new != *old {
+                *old = new;
+                result.push(file.clone());
+            }
+        }
+        result
+    }
+}
+
+fn stamp(file: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = fs::metadata(file).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// The files a flattened solution was made of: the solution, its local modules, and the
+/// sources of the inlined library modules if this crate's source is still around. Library
+/// modules are built into rlfmt, so changing them only has an effect after reinstalling it.
+pub fn watched_files(solution: &Path, flattened: &Flattened) -> Vec<PathBuf> {
+    let mut result = vec![solution.to_path_buf()];
+    for origin in &flattened.origins {
+        let file = match origin {
+            Origin::File(file, _) => PathBuf::from(file),
+            Origin::Library(module, _) => library_file(module),
+            _ => continue,
+        };
+        if !result.contains(&file) && (file.is_file() || matches!(origin, Origin::File(..))) {
+            result.push(file);
+        }
+    }
+    result
+}
+
+/// Where the source of a library module was when rlfmt was built.
+pub fn library_file(module: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join(format!("{}.rs", module))
+}
+
+/// A one line summary of the size of the flattened code, and how much of it is inlined.
+///
+/// # Example
+/// ```
+/// use cauly_rust_leetcode_utils::rlfmt::*;
+/// let mut code = Flattened::default();
+/// code.push("struct A;\n", Origin::Library("grid", Some(1)));
+/// code.push("fn f() {}\n", Origin::Solution(1));
+/// assert_eq!("20 bytes, 2 lines (1 inlined from grid)", summary(&code));
+/// ```
+pub fn summary(flattened: &Flattened) -> String {
+    let mut modules: Vec<&str> = Vec::new();
+    let mut inlined = 0;
+    for origin in &flattened.origins {
+        if let Origin::Library(module, _) = origin {
+            inlined += 1;
+            if !modules.contains(module) {
+                modules.push(module);
+            }
+        }
+    }
+    let mut result = format!(
+        "{} bytes, {} lines",
+        flattened.code.len(),
+        flattened.code.lines().count()
+    );
+    if inlined > 0 {
+        result.push_str(&format!(
+            " ({} inlined from {})",
+            inlined,
+            modules.join(", ")
+        ));
+    }
+    result
+}
@@ -0,0 +1,228 @@
+use super::items::{parse_items, ItemKind};
+use super::source_map::{Flattened, Origin};
+use super::strip::is_test_attr;
+use super::{flatten_mapped, Options};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Find the solution file of a workspace with one problem per file, i.e. `src/*.rs` and
+/// `src/bin/*.rs` under `root`. `pattern` is either a problem id, matching `p0001_two_sum.rs`
+/// for `1` or `0001`, or a glob with `*` and `?` matched against the file name or the path
+/// relative to `root`. Fails unless exactly one file matches.
+pub fn find_problem(root: &Path, pattern: &str) -> Result<PathBuf, String> {
+    let mut candidates = Vec::new();
+    for dir in ["src", "src/bin"] {
+        if let Ok(entries) = fs::read_dir(root.join(dir)) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.is_file() && path.extension().is_some_and(|e| e == "rs") {
+                    candidates.push(path);
+                }
+            }
+        }
+    }
+    candidates.sort();
+
+    let id = pattern
+        .trim_start_matches(['p', 'P'])
+        .parse::<u32>()
+        .ok()
+        .filter(|_| {
+            pattern
+                .trim_start_matches(['p', 'P'])
+                .chars()
+                .all(|c| c.is_ascii_digit())
+        });
+    let matches: Vec<PathBuf> = candidates
+        .into_iter()
+        .filter(|path| {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            let relative = path.strip_prefix(root).unwrap_or(path).to_string_lossy();
+            match id {
+                Some(id) => problem_id(&name) == Some(id),
+                None => glob_match(pattern, &name) || glob_match(pattern, &relative),
+            }
+        })
+        .collect();
+    match matches.len() {
+        0 => Err(format!(
+            "no problem file matches `{}` in {}/src or {}/src/bin",
+            pattern,
+            root.display(),
+            root.display()
+        )),
+        1 => Ok(matches.into_iter().next().unwrap_or_default()),
+        _ => Err(format!(
+            "`{}` matches more than one file: {}",
+            pattern,
+            matches
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+/// The problem id of a file named like `p0001_two_sum.rs` or `p1.rs`.
+fn problem_id(file_name: &str) -> Option<u32> {
+    let stem = file_name.strip_suffix(".rs")?.strip_prefix('p')?;
+    let digits = stem.split('_').next()?;
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+/// Match `text` against a glob where `*` matches any run of characters and `?` any one.
+///
+/// # Example
+/// ```
+/// use cauly_rust_leetcode_utils::rlfmt::*;
+/// assert!(glob_match("p00*_two?sum.rs", "p0001_two_sum.rs"));
+/// assert!(glob_match("src/bin/*", "src/bin/a.rs"));
+/// assert!(!glob_match("*.rs", "a.rs.bak"));
+/// ```
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // where the last `*` is, and the position in text it currently stands for
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Read a solution file and replace its `mod foo;` declarations with the content of the
+/// files they refer to, recursively, as `mod foo { ... }`. Lines of the file itself have
+/// [`Origin::Solution`], lines of the module files [`Origin::File`]. Test modules are only
+/// followed if `keep_tests` is set, since they are removed otherwise.
+pub fn expand_modules(path: &Path, keep_tests: bool) -> Result<Flattened, String> {
+    let mut stack = Vec::new();
+    let expanded = expand_file(path, keep_tests, &mut stack)?;
+    let name = path.display().to_string();
+    let mut result = Flattened::default();
+    let mut lines = expanded.code.split_inclusive('\n');
+    for origin in expanded.origins {
+        let origin = match origin {
+            Origin::File(file, n) if file == name => Origin::Solution(n),
+            o => o,
+        };
+        if let Some(line) = lines.next() {
+            result.push(line, origin);
+        }
+    }
+    Ok(result)
+}
+
+/// Flatten a solution file, together with the local modules it declares.
+pub fn flatten_file(path: &Path, options: &Options) -> Result<Flattened, String> {
+    let expanded = expand_modules(path, options.keep_tests)?;
+    let flattened = flatten_mapped(&expanded.code, options)
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(flattened.compose(&expanded))
+}
+
+fn expand_file(
+    path: &Path,
+    keep_tests: bool,
+    stack: &mut Vec<PathBuf>,
+) -> Result<Flattened, String> {
+    if stack.iter().any(|p| p == path) {
+        return Err(format!("`{}` includes itself", path.display()));
+    }
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("can't read `{}`: {}", path.display(), e))?;
+    stack.push(path.to_path_buf());
+
+    let name = path.display().to_string();
+    let line_of = |pos: usize| content[..pos].matches('\n').count() + 1;
+    let mut result = Flattened::default();
+    let mut pos = 0;
+    for item in parse_items(&content, (0, content.len())) {
+        let is_declaration =
+            item.kind == ItemKind::Mod && item.body.is_none() && content[..item.end].ends_with(';');
+        if !is_declaration || (!keep_tests && item.attrs.iter().any(|a| is_test_attr(a))) {
+            continue;
+        }
+        let module = item.name.clone().unwrap_or_default();
+        let file = module_file(path, &module, &item.attrs)?;
+        let inner = expand_file(&file, keep_tests, stack)?;
+
+        let semicolon = item.end - 1;
+        result.push(
+            &content[pos..semicolon],
+            Origin::File(name.clone(), line_of(pos)),
+        );
+        result.push(" {\n", Origin::Generated);
+        let mut lines = inner.code.split_inclusive('\n');
+        for origin in inner.origins {
+            if let Some(line) = lines.next() {
+                result.push(line, origin);
+            }
+        }
+        if !result.code.ends_with('\n') {
+            result.push("\n", Origin::Generated);
+        }
+        result.push("}", Origin::File(name.clone(), line_of(semicolon)));
+        pos = item.end;
+    }
+    result.push(&content[pos..], Origin::File(name, line_of(pos)));
+    stack.pop();
+    Ok(result)
+}
+
+/// The file of `mod module;` declared in `path`, following the rules of rustc: modules of
+/// crate roots and `mod.rs` files live next to them, modules of other files in a directory
+/// named after the file. A `#[path = "..."]` attribute is relative to the declaring file.
+fn module_file(path: &Path, module: &str, attrs: &[String]) -> Result<PathBuf, String> {
+    let parent = path.parent().unwrap_or(Path::new(""));
+    for attr in attrs {
+        let compact: String = attr.chars().filter(|c| !c.is_whitespace()).collect();
+        if let Some(value) = compact
+            .strip_prefix("#[path=\"")
+            .and_then(|rest| rest.strip_suffix("\"]"))
+        {
+            return Ok(parent.join(value));
+        }
+    }
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let is_root = matches!(stem.as_ref(), "lib" | "main" | "mod")
+        || parent.file_name().is_some_and(|d| d == "bin");
+    let dir = if is_root {
+        parent.to_path_buf()
+    } else {
+        parent.join(stem.as_ref())
+    };
+    let candidates = [
+        dir.join(format!("{}.rs", module)),
+        dir.join(module).join("mod.rs"),
+    ];
+    candidates
+        .iter()
+        .find(|f| f.is_file())
+        .cloned()
+        .ok_or_else(|| {
+            format!(
+                "can't find module `{}` declared in `{}`, expected {} or {}",
+                module,
+                path.display(),
+                candidates[0].display(),
+                candidates[1].display()
+            )
+        })
+}
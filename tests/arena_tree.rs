@@ -0,0 +1,125 @@
+extern crate cauly_rust_leetcode_utils;
+use cauly_rust_leetcode_utils::arena_tree::*;
+use cauly_rust_leetcode_utils::binary_tree::*;
+
+#[test]
+fn test1() {
+    for s in [
+        "[]",
+        "[1]",
+        "[1,null,2,3]",
+        "[5,4,8,11,null,13,4,7,2,null,null,null,1]",
+    ] {
+        let tree = TreeNode::from_string(s).unwrap();
+        let arena = ArenaTree::from_tree(&tree);
+        assert_eq!(tree, arena.to_tree());
+        assert_eq!(s, arena.to_leetcode_string());
+        assert_eq!(Ok(arena), ArenaTree::from_string(s));
+    }
+    assert!(ArenaTree::from_string("[1,x]").is_err());
+    assert!(ArenaTree::new().to_tree().is_none());
+}
+
+#[test]
+fn test2() {
+    let tree = TreeNode::from_string("[1,2,3,4,5,null,6]").unwrap();
+    let arena = ArenaTree::from_tree(&tree);
+    for t in [
+        TraversalType::Preorder,
+        TraversalType::Inorder,
+        TraversalType::Postorder,
+        TraversalType::LevelOrder,
+    ] {
+        let expected: Vec<i32> = tree.traverse(t).map(|v| v.node.borrow().val).collect();
+        let mut values = Vec::new();
+        arena.walk(t, |i| values.push(arena.values[i]));
+        assert_eq!(expected, values);
+    }
+    assert_eq!(tree.levels().collect::<Vec<_>>(), arena.levels());
+    assert_eq!(vec![0, 1, 1, 2, 2, 2], arena.depths());
+    assert_eq!(3, arena.height());
+    let sum = arena.aggregate(
+        |i| arena.values[i],
+        |v, l, r| Some(v + l.unwrap_or(0) + r.unwrap_or(0)),
+    );
+    assert_eq!(Some(21), sum);
+    assert_eq!(
+        Some(3),
+        arena.traverse(TraversalType::Inorder).position(|i| i == 0)
+    );
+}
+
+#[test]
+fn test3() {
+    let mut arena = ArenaTree::new();
+    let root = arena.add_node(2);
+    let one = arena.add_node(1);
+    let three = arena.add_node(3);
+    arena.set_left(root, Some(one));
+    arena.set_right(root, Some(three));
+    assert_eq!("[2,1,3]", arena.to_leetcode_string());
+    // detach a subtree and hang it on the other side
+    arena.set_left(root, None);
+    arena.set_right(three, Some(one));
+    assert_eq!(None, arena.left[root]);
+    assert_eq!(Some(three), arena.parent[one]);
+    assert_eq!("[2,null,3,null,1]", arena.to_leetcode_string());
+    assert_eq!(3, arena.len());
+    // a skewed tree of 100000 nodes
+    let mut deep = ArenaTree::new();
+    for i in 0..100_000 {
+        let node = deep.add_node(i);
+        if i > 0 {
+            deep.set_right(node - 1, Some(node));
+        }
+    }
+    assert_eq!(100_000, deep.height());
+    assert_eq!(Some(99_999), deep.traverse(TraversalType::Postorder).next());
+}
+
+#[test]
+fn test4() {
+    let mut arena = ArenaTree::from_string("[1,2,3,4,5]").unwrap();
+    // move 4 from the left of 2 to the right of 3, without detaching it first
+    arena.set_right(2, Some(3));
+    assert_eq!((None, Some(2)), (arena.left[1], arena.parent[3]));
+    assert_eq!("[1,2,3,null,5,null,4]", arena.to_leetcode_string());
+    let tree = arena.to_tree();
+    assert_eq!(5, tree.node_count());
+    // move 2 to the other side of its own parent
+    arena.set_right(0, Some(1));
+    assert_eq!((None, Some(1)), (arena.left[0], arena.right[0]));
+    assert_eq!((Some(0), None), (arena.parent[1], arena.parent[2]));
+    assert_eq!("[1,null,2,null,5]", arena.to_leetcode_string());
+    // setting the same child again keeps it
+    arena.set_right(0, Some(1));
+    assert_eq!((Some(1), Some(0)), (arena.right[0], arena.parent[1]));
+}
+
+#[test]
+fn test5() {
+    // a node can't go below itself or its descendants
+    for (node, child) in [(1, 0), (1, 1), (3, 1)] {
+        let result = std::panic::catch_unwind(|| {
+            let mut arena = ArenaTree::from_string("[1,2,3,4]").unwrap();
+            arena.set_left(node, Some(child));
+        });
+        assert!(result.is_err(), "{} below {}", child, node);
+    }
+
+    // the root moved below a detached node
+    let mut arena = ArenaTree::from_string("[1,2,3]").unwrap();
+    let top = arena.add_node(0);
+    arena.set_right(top, Some(0));
+    assert_eq!(Some(top), arena.root);
+    assert_eq!("[0,null,1,2,3]", arena.to_leetcode_string());
+    assert_eq!(3, arena.height());
+    // the root moved below a node of another detached subtree
+    let mut arena = ArenaTree::from_string("[1,2]").unwrap();
+    let a = arena.add_node(5);
+    let b = arena.add_node(6);
+    arena.set_left(a, Some(b));
+    arena.set_right(b, Some(0));
+    assert_eq!(Some(a), arena.root);
+    assert_eq!("[5,6,null,null,1,2]", arena.to_leetcode_string());
+}
@@ -1,15 +1,18 @@
 extern crate cauly_rust_leetcode_utils;
 use cauly_rust_leetcode_utils::binary_tree::*;
+use cauly_rust_leetcode_utils::random::*;
+use std::cell::RefCell;
+use std::rc::Rc;
 
 #[test]
 fn test1() {
-    let result = TreeNode::from_string("1").unwrap();
+    let result = TreeNode::from_string("1").unwrap().unwrap();
     assert_eq!(1, result.borrow().val);
 }
 
 #[test]
 fn test2() {
-    let result = TreeNode::from_string("1,2,3");
+    let result = TreeNode::from_string("1,2,3").unwrap();
     result.walk(TraversalType::Inorder, |mut x| {
         x.set_val(x.get_val().unwrap() + 1)
     });
@@ -19,7 +22,7 @@ fn test2() {
 }
 #[test]
 fn test3() {
-    let result = TreeNode::from_string("1,null,3,4,5");
+    let result = TreeNode::from_string("1,null,3,4,5").unwrap();
     assert_eq!(Some(1), result.get_val());
     assert_eq!(None, result.get_left());
     assert_eq!(Some(3), result.get_right().get_val());
@@ -29,7 +32,7 @@ fn test3() {
 
 #[test]
 fn test4() {
-    let result = TreeNode::from_string("1,null,null");
+    let result = TreeNode::from_string("1,null,null").unwrap();
     assert_eq!(Some(1), result.get_val());
     assert_eq!(None, result.get_left());
     assert_eq!(None, result.get_right());
@@ -37,7 +40,7 @@ fn test4() {
 
 #[test]
 fn test5() {
-    let result = TreeNode::from_string("1,2,3,4,5");
+    let result = TreeNode::from_string("1,2,3,4,5").unwrap();
     let sum = result.aggregate(
         |n| n.get_val().unwrap_or(0),
         |v, l, r| Some(v + l.unwrap_or(0) + r.unwrap_or(0)),
@@ -47,7 +50,7 @@ fn test5() {
 
 #[test]
 fn test6() {
-    let result = TreeNode::from_string("1,2,3");
+    let result = TreeNode::from_string("1,2,3").unwrap();
     let mut v = Vec::new();
     result.walk_t(TraversalType::Inorder, &mut v, |v, x| match x.get_val() {
         Some(i) => v.push(i),
@@ -58,13 +61,13 @@ fn test6() {
 
 #[test]
 fn test7() {
-    let result = TreeNode::from_string("1,null,2,3");
+    let result = TreeNode::from_string("1,null,2,3").unwrap();
     assert_eq!(Some(3), result.get_right().get_left().get_val());
 }
 
 #[test]
 fn test8() {
-    let result = TreeNode::from_string("1,null,2,3");
+    let result = TreeNode::from_string("1,null,2,3").unwrap();
     let mut count = 0;
     let sum = result.aggregate_t(
         &mut count,
@@ -77,3 +80,515 @@ fn test8() {
     assert_eq!(3, count);
     assert_eq!(Some(6), sum);
 }
+
+#[test]
+fn test9() {
+    for s in [
+        "[1,2,null,3]",
+        "[-1,null,-20]",
+        "[5,4,8,11,null,13,4,7,2,null,null,null,1]",
+        "[]",
+    ] {
+        assert_eq!(s, TreeNode::from_string(s).unwrap().to_leetcode_string());
+    }
+    let result = TreeNode::from_string(" [1, 2, null, null, -3, null, null] ").unwrap();
+    assert_eq!("[1,2,null,null,-3]", result.to_leetcode_string());
+    assert_eq!(Some(-3), result.get_left().get_right().get_val());
+    assert_eq!(Ok(None), TreeNode::from_string("[null]"));
+    assert_eq!("[]", None.to_leetcode_string());
+    assert!(TreeNode::from_string("[1,x]").is_err());
+    assert!(TreeNode::from_string("[1,null,null,2]").is_err());
+    assert!(TreeNode::from_string("[null,1]").is_err());
+}
+
+#[test]
+fn test10() {
+    let tree = TreeNode::from_string("[10,5,-3,3,2,null,11,3,-2,null,1]").unwrap();
+    assert_eq!(
+        "       10
+    ┌───┴─┐
+    5    -3
+ ┌──┴┐    └─┐
+ 3   2     11
+┌┴─┐ └┐
+3 -2  1
+",
+        tree.draw(&Default::default())
+    );
+    let options = DrawOptions {
+        layout: TreeLayout::Sideways,
+        marked: vec![
+            tree.get_left().unwrap(),
+            tree.get_left().get_right().unwrap(),
+        ],
+        ..Default::default()
+    };
+    assert_eq!(
+        "    ┌── 11
+┌── -3
+10
+│       ┌── 1
+│   ┌── [2]
+└── [5]
+    │   ┌── -2
+    └── 3
+        └── 3
+",
+        tree.draw(&options)
+    );
+    let ascii = tree.draw(&DrawOptions {
+        ascii: true,
+        ..options.clone()
+    });
+    assert!(ascii.is_ascii());
+    assert!(ascii.contains("\n|       +-- 1\n"));
+    assert_eq!("", None.draw(&options));
+}
+
+#[test]
+fn test11() {
+    let tree = TreeNode::from_string("[1,2,3,4,5,null,6]").unwrap();
+    let order =
+        |t: TraversalType| -> Vec<i32> { tree.traverse(t).map(|v| v.node.borrow().val).collect() };
+    assert_eq!(vec![1, 2, 4, 5, 3, 6], order(TraversalType::Preorder));
+    assert_eq!(vec![4, 2, 5, 1, 3, 6], order(TraversalType::Inorder));
+    assert_eq!(vec![4, 5, 2, 6, 3, 1], order(TraversalType::Postorder));
+    assert_eq!(vec![1, 2, 3, 4, 5, 6], order(TraversalType::LevelOrder));
+    let parents: Vec<(i32, usize, Option<i32>)> = tree
+        .traverse(TraversalType::LevelOrder)
+        .map(|v| (v.node.borrow().val, v.depth, v.parent.get_val()))
+        .collect();
+    assert_eq!(
+        vec![
+            (1, 0, None),
+            (2, 1, Some(1)),
+            (3, 1, Some(1)),
+            (4, 2, Some(2)),
+            (5, 2, Some(2)),
+            (6, 2, Some(3))
+        ],
+        parents
+    );
+    assert_eq!(0, None.traverse(TraversalType::Inorder).count());
+
+    // closures can capture their environment and stop early
+    let mut visited = Vec::new();
+    tree.walk(TraversalType::Postorder, |n| {
+        visited.push(n.get_val().unwrap())
+    });
+    assert_eq!(order(TraversalType::Postorder), visited);
+    let first_leaf = tree
+        .traverse(TraversalType::Preorder)
+        .find(|v| v.node.borrow().left.is_none() && v.node.borrow().right.is_none());
+    assert_eq!(Some(4), first_leaf.map(|v| v.node.borrow().val));
+    let limit = 3;
+    let small = tree.aggregate(
+        |n| (n.get_val().unwrap() <= limit) as i32,
+        |v, l, r| Some(v + l.unwrap_or(0) + r.unwrap_or(0)),
+    );
+    assert_eq!(Some(3), small);
+}
+
+#[test]
+fn test12() {
+    // a skewed tree deep enough to overflow a recursive traversal
+    let n = 200_000;
+    let root = Some(Rc::new(RefCell::new(TreeNode::new(0))));
+    let mut last = root.clone();
+    for i in 1..n {
+        let node = Some(Rc::new(RefCell::new(TreeNode::new(i))));
+        last.set_sub_nodes(&None, &node);
+        last = node;
+    }
+    assert_eq!(n as usize, root.traverse(TraversalType::Inorder).count());
+    let height = root.aggregate(
+        |_| 1,
+        |v, l: Option<i32>, r: Option<i32>| Some(v + l.unwrap_or(0).max(r.unwrap_or(0))),
+    );
+    assert_eq!(Some(n), height);
+    let mut sum = 0i64;
+    root.walk(TraversalType::Preorder, |n| {
+        sum += n.get_val().unwrap() as i64
+    });
+    assert_eq!((n as i64 - 1) * n as i64 / 2, sum);
+    let mut node = root;
+    while let Some(n) = node.take() {
+        node = n.borrow_mut().right.take();
+    }
+}
+
+#[test]
+fn test13() {
+    let tree = TreeNode::from_string("[1,2,3,4,null,5,6,null,7,-8]").unwrap();
+    assert_eq!(
+        vec![vec![1], vec![2, 3], vec![4, 5, 6], vec![7, -8]],
+        tree.levels().collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec![vec![1], vec![3, 2], vec![4, 5, 6], vec![-8, 7]],
+        tree.zigzag_levels()
+    );
+    assert_eq!(vec![1, 3, 6, -8], tree.right_view());
+    assert_eq!(vec![1, 2, 4, 7], tree.left_view());
+    assert_eq!(
+        vec![vec![4], vec![2, 7, -8], vec![1, 5], vec![3], vec![6]],
+        tree.vertical_order()
+    );
+    assert_eq!(vec![1, 3, 6, 7], tree.level_max());
+    assert_eq!(vec![1, 5, 15, -1], tree.level_sum());
+    assert_eq!(vec![1.0, 2.5, 5.0, -0.5], tree.level_average());
+
+    let empty: Option<Rc<RefCell<TreeNode>>> = None;
+    assert_eq!(0, empty.levels().count());
+    assert!(empty.vertical_order().is_empty());
+    assert!(empty.level_average().is_empty());
+}
+
+#[test]
+fn test14() {
+    let order = |tree: &Option<Rc<RefCell<TreeNode>>>, t: TraversalType| -> Vec<i32> {
+        tree.traverse(t).map(|v| v.node.borrow().val).collect()
+    };
+    for s in [
+        "[1]",
+        "[1,2]",
+        "[1,null,2]",
+        "[3,9,20,null,null,15,7]",
+        "[5,-4,8,11,null,13,4,7,2,null,null,null,1]",
+    ] {
+        let tree = TreeNode::from_string(s).unwrap();
+        let pre = order(&tree, TraversalType::Preorder);
+        let ino = order(&tree, TraversalType::Inorder);
+        let post = order(&tree, TraversalType::Postorder);
+        assert_eq!(
+            s,
+            TreeNode::from_preorder_inorder(&pre, &ino)
+                .unwrap()
+                .to_leetcode_string()
+        );
+        assert_eq!(
+            s,
+            TreeNode::from_inorder_postorder(&ino, &post)
+                .unwrap()
+                .to_leetcode_string()
+        );
+    }
+    let full = TreeNode::from_string("[1,2,3,null,null,4,5,6,7]").unwrap();
+    let pre = order(&full, TraversalType::Preorder);
+    let post = order(&full, TraversalType::Postorder);
+    assert_eq!(
+        full,
+        TreeNode::from_preorder_postorder(&pre, &post).unwrap()
+    );
+    assert_eq!(
+        "[1,2,null,3]",
+        TreeNode::from_preorder_postorder(&[1, 2, 3], &[3, 2, 1])
+            .unwrap()
+            .to_leetcode_string()
+    );
+    assert_eq!(Ok(None), TreeNode::from_preorder_inorder(&[], &[]));
+
+    assert!(TreeNode::from_preorder_inorder(&[1, 2], &[1]).is_err());
+    assert!(TreeNode::from_preorder_inorder(&[1, 1], &[1, 1]).is_err());
+    assert!(TreeNode::from_preorder_inorder(&[1, 2, 3], &[3, 1, 2]).is_err());
+    assert!(TreeNode::from_inorder_postorder(&[1, 2, 3], &[3, 1, 2]).is_err());
+    assert!(TreeNode::from_preorder_postorder(&[1, 2, 3], &[2, 1, 3]).is_err());
+
+    let sorted: Vec<i32> = (0..100).collect();
+    let bst = TreeNode::from_sorted(&sorted).unwrap();
+    assert_eq!(sorted, order(&bst, TraversalType::Inorder));
+    assert_eq!(7, bst.levels().count());
+    let pre = order(&bst, TraversalType::Preorder);
+    assert_eq!(bst, TreeNode::from_bst_preorder(&pre).unwrap());
+    assert!(TreeNode::from_bst_preorder(&[2, 2]).is_err());
+    assert_eq!(Ok(None), TreeNode::from_sorted(&[]));
+
+    // a skewed tree of 100000 nodes
+    let n = 100_000;
+    let pre: Vec<i32> = (0..n).collect();
+    let ino: Vec<i32> = (0..n).rev().collect();
+    let mut tree = TreeNode::from_preorder_inorder(&pre, &ino).unwrap();
+    assert_eq!(n as usize, tree.levels().count());
+    while let Some(node) = tree.take() {
+        tree = node.borrow_mut().left.take();
+    }
+    let mut tree = TreeNode::from_bst_preorder(&pre).unwrap();
+    assert_eq!(n as usize, tree.right_view().len());
+    while let Some(node) = tree.take() {
+        tree = node.borrow_mut().right.take();
+    }
+}
+
+#[test]
+fn test15() {
+    let mut tree = None;
+    let mut values = Vec::new();
+    let mut x = 7u32;
+    for _ in 0..200 {
+        x = x.wrapping_mul(1_103_515_245).wrapping_add(12345) % 1000;
+        let v = x as i32 - 500;
+        assert_eq!(!values.contains(&v), tree.bst_insert(v));
+        if !values.contains(&v) {
+            values.push(v);
+        }
+    }
+    values.sort_unstable();
+    assert!(tree.is_valid_bst());
+    assert_eq!(values, tree.bst_iter().collect::<Vec<_>>());
+    for probe in [-600, -500, -123, 0, 1, 250, 499, 600] {
+        let floor = values.iter().rev().find(|&&v| v <= probe).cloned();
+        let ceil = values.iter().find(|&&v| v >= probe).cloned();
+        assert_eq!((floor, ceil), (tree.bst_floor(probe), tree.bst_ceil(probe)));
+        assert_eq!(values.contains(&probe), tree.bst_search(probe).is_some());
+    }
+    assert_eq!(Some(values[0]), tree.bst_kth_smallest(1));
+    assert_eq!(Some(values[9]), tree.bst_kth_smallest(10));
+    assert_eq!(None, tree.bst_kth_smallest(0));
+    assert_eq!(None, tree.bst_kth_smallest(values.len() + 1));
+    let in_range = || values.iter().filter(|&&v| (-100..=200).contains(&v));
+    assert_eq!(
+        in_range().map(|&v| v as i64).sum::<i64>(),
+        tree.bst_range_sum(-100, 200)
+    );
+    assert_eq!(in_range().count(), tree.bst_range_count(-100, 200));
+
+    // delete the root, leaves and inner nodes until the tree is empty
+    while let Some(root) = tree.get_val() {
+        let victim = if values.len() % 3 == 0 {
+            root
+        } else {
+            values[values.len() / 2]
+        };
+        assert!(tree.bst_delete(victim));
+        assert!(!tree.bst_delete(victim));
+        values.retain(|&v| v != victim);
+        assert!(tree.is_valid_bst());
+        assert_eq!(values, tree.bst_iter().collect::<Vec<_>>());
+    }
+    assert!(values.is_empty());
+    assert!(!tree.bst_iter().has_next());
+
+    let extremes = TreeNode::from_string("[0,-2147483648,2147483647]").unwrap();
+    assert!(extremes.is_valid_bst());
+    assert!(!TreeNode::from_string("[5,1,4,null,null,3,6]")
+        .unwrap()
+        .is_valid_bst());
+    assert!(!TreeNode::from_string("[2,2]").unwrap().is_valid_bst());
+}
+
+#[test]
+fn test16() {
+    let tree = TreeNode::from_string("[3,5,1,6,2,0,8,null,null,7,4]").unwrap();
+    let find = |val: i32| {
+        tree.traverse(TraversalType::Preorder)
+            .find(|v| v.node.borrow().val == val)
+            .map(|v| v.node)
+    };
+    let (five, four, seven, one) = (
+        find(5).unwrap(),
+        find(4).unwrap(),
+        find(7).unwrap(),
+        find(1).unwrap(),
+    );
+    assert_eq!(4, tree.height());
+    assert_eq!(9, tree.node_count());
+    assert_eq!(5, tree.diameter());
+    assert_eq!(Some(26), tree.max_path_sum());
+    assert_eq!(Some(3), tree.lca(&five, &one).get_val());
+    assert_eq!(Some(5), tree.lca(&five, &four).get_val());
+    assert_eq!(Some(2), tree.lca_by_val(7, 4).get_val());
+    assert_eq!(None, tree.lca_by_val(7, 42));
+    let path: Vec<i32> = tree
+        .path_to(&seven)
+        .unwrap()
+        .iter()
+        .map(|n| n.borrow().val)
+        .collect();
+    assert_eq!(vec![3, 5, 2, 7], path);
+    assert!(tree
+        .path_to(&Rc::new(RefCell::new(TreeNode::new(7))))
+        .is_none());
+    assert_eq!(
+        vec![
+            vec![3, 5, 6],
+            vec![3, 5, 2, 7],
+            vec![3, 5, 2, 4],
+            vec![3, 1, 0],
+            vec![3, 1, 8]
+        ],
+        tree.root_to_leaf_paths()
+    );
+    let parents = tree.parent_map();
+    assert_eq!(8, parents.len());
+    assert_eq!(
+        Some(2),
+        parents.get(&Rc::as_ptr(&seven)).map(|p| p.borrow().val)
+    );
+    assert_eq!(vec![7, 4, 1], tree.distance_k(&five, 2));
+    assert_eq!(vec![5], tree.distance_k(&five, 0));
+    assert!(tree.distance_k(&five, 9).is_empty());
+
+    assert_eq!(
+        Some(-3),
+        TreeNode::from_string("[-3]").unwrap().max_path_sum()
+    );
+    assert_eq!(
+        Some(-1),
+        TreeNode::from_string("[-2,-1]").unwrap().max_path_sum()
+    );
+    let empty: Option<Rc<RefCell<TreeNode>>> = None;
+    assert_eq!(
+        (0, 0, 0, None),
+        (
+            empty.height(),
+            empty.node_count(),
+            empty.diameter(),
+            empty.max_path_sum()
+        )
+    );
+    assert!(empty.root_to_leaf_paths().is_empty());
+
+    // a skewed tree of 100000 nodes
+    let n = 100_000;
+    let values: Vec<i32> = (0..n).collect();
+    let mut deep = TreeNode::from_bst_preorder(&values).unwrap();
+    let last = deep.bst_search(n - 1).unwrap();
+    assert_eq!(
+        (n as usize, n as usize - 1),
+        (deep.height(), deep.diameter())
+    );
+    assert_eq!(Some(n as i64 * (n as i64 - 1) / 2), deep.max_path_sum());
+    assert_eq!(n as usize, deep.path_to(&last).unwrap().len());
+    assert_eq!(Some(n - 2), deep.lca_by_val(n - 2, n - 1).get_val());
+    assert_eq!(vec![n - 11], deep.distance_k(&last, 10));
+    while let Some(node) = deep.take() {
+        deep = node.borrow_mut().right.take();
+    }
+}
+
+#[test]
+fn test17() {
+    let tree = |s: &str| TreeNode::from_string(s).unwrap();
+    let a = tree("[3,4,5,1,2,null,null,null,null,0]");
+    assert_eq!(Ok(()), a.compare(&a.clone()));
+    assert_eq!(
+        Err("root: expected 1, got 3".to_string()),
+        a.compare(&tree("[1]"))
+    );
+    assert_eq!(
+        Err("root.left.right.left: expected null, got 0".to_string()),
+        a.compare(&tree("[3,4,5,1,2]"))
+    );
+    assert_eq!(
+        Err("root: expected 1, got null".to_string()),
+        None.compare(&tree("[1]"))
+    );
+
+    assert!(a.is_subtree(&tree("[2,0]")));
+    assert!(!a.is_subtree(&tree("[4,1,2]")));
+    assert!(a.is_subtree(&None));
+    assert!(!None.is_subtree(&tree("[1]")));
+
+    let b = tree("[3,5,4,null,null,2,1,null,0]");
+    assert!(a.is_mirror(&b));
+    assert!(b.is_mirror(&a));
+    assert!(!a.is_symmetric());
+    assert!(tree("[1,2,2,null,3,3]").is_symmetric());
+    assert!(!tree("[1,2,2,null,3,null,3]").is_symmetric());
+    assert!(None.is_symmetric());
+
+    assert!(a.is_isomorphic(&b));
+    assert!(tree("[1,2,3,4,5,6,null,null,null,7,8]")
+        .is_isomorphic(&tree("[1,3,2,null,6,4,5,null,null,null,null,8,7]")));
+    assert!(!a.is_isomorphic(&tree("[3,4,5,1,2,null,null,0]")));
+
+    let duplicates = tree("[2,1,1]").duplicate_subtrees();
+    assert_eq!(vec!["[1]"], duplicates_text(&duplicates));
+    let duplicates = tree("[1,2,3,4,null,2,4,null,null,4]").duplicate_subtrees();
+    assert_eq!(vec!["[4]", "[2,4]"], duplicates_text(&duplicates));
+    assert!(tree("[1,2,3]").duplicate_subtrees().is_empty());
+
+    // comparisons of deep trees don't recurse
+    let n = 100_000;
+    let values: Vec<i32> = (0..n).collect();
+    let mut deep = TreeNode::from_bst_preorder(&values).unwrap();
+    let mut other = TreeNode::from_bst_preorder(&values).unwrap();
+    assert_eq!(Ok(()), deep.compare(&other));
+    assert!(deep.is_subtree(&other.get_right()));
+    assert!(deep.is_isomorphic(&other));
+    assert!(deep.duplicate_subtrees().is_empty());
+    for tree in [&mut deep, &mut other] {
+        while let Some(node) = tree.take() {
+            *tree = node.borrow_mut().right.take();
+        }
+    }
+}
+
+#[test]
+fn test19() {
+    // comparing a skewed tree of 100000 nodes keeps only the path to the current node
+    let n = 100_000;
+    let (mut tree, s) = Rng::new(7).skewed_tree(n, -9, 9, Skew::Random);
+    let mut copy = TreeNode::from_string(&s).unwrap();
+    assert_eq!(Ok(()), tree.compare(&copy));
+    let deepest = copy.traverse(TraversalType::Preorder).last().unwrap().node;
+    deepest.borrow_mut().val = 10;
+    let error = tree.compare(&copy).unwrap_err();
+    assert_eq!(n, error.split(':').next().unwrap().split('.').count());
+    assert!(error.starts_with("root."));
+    assert!(
+        error.contains(": expected 10, got "),
+        "{}",
+        &error[error.len() - 30..]
+    );
+    assert!(tree.is_subtree(&tree.get_left().or_else(|| tree.get_right())));
+    for tree in [&mut tree, &mut copy] {
+        while let Some(node) = tree.take() {
+            let mut n = node.borrow_mut();
+            *tree = n.left.take().or_else(|| n.right.take());
+        }
+    }
+}
+
+fn duplicates_text(nodes: &[Rc<RefCell<TreeNode>>]) -> Vec<String> {
+    nodes
+        .iter()
+        .map(|n| Some(Rc::clone(n)).to_leetcode_string())
+        .collect()
+}
+
+#[test]
+fn test18() {
+    // drawing doesn't recurse, so deep trees fit in a small stack
+    let thread = std::thread::Builder::new().stack_size(128 * 1024);
+    let handle = thread.spawn(|| {
+        let n = 3000;
+        let values: Vec<i32> = (0..n).collect();
+        let mut tree = TreeNode::from_bst_preorder(&values).unwrap();
+        let options = DrawOptions {
+            layout: TreeLayout::Sideways,
+            ..Default::default()
+        };
+        let drawing = tree.draw(&options);
+        let lines: Vec<&str> = drawing.lines().collect();
+        assert_eq!(n as usize, lines.len());
+        assert_eq!(
+            format!("{}┌── {}", " ".repeat(4 * (n as usize - 2)), n - 1),
+            lines[0]
+        );
+        assert_eq!("    ┌── 2", lines[n as usize - 3]);
+        assert_eq!("0", lines[n as usize - 1]);
+
+        let mut short = TreeNode::from_bst_preorder(&values[..300]).unwrap();
+        let drawing = short.draw(&Default::default());
+        assert_eq!(599, drawing.lines().count());
+        assert!(drawing.starts_with("0\n└┐\n 1\n"));
+
+        for tree in [&mut tree, &mut short] {
+            while let Some(node) = tree.take() {
+                *tree = node.borrow_mut().right.take();
+            }
+        }
+    });
+    handle.unwrap().join().unwrap();
+}
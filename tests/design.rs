@@ -0,0 +1,137 @@
+extern crate cauly_rust_leetcode_utils;
+use cauly_rust_leetcode_utils::design::*;
+use cauly_rust_leetcode_utils::design_dispatch;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+
+struct LRUCache {
+    capacity: usize,
+    values: HashMap<i32, i32>,
+    order: VecDeque<i32>,
+}
+
+impl LRUCache {
+    fn new(capacity: i32) -> Self {
+        LRUCache {
+            capacity: capacity as usize,
+            values: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    fn touch(&mut self, key: i32) {
+        self.order.retain(|&k| k != key);
+        self.order.push_back(key);
+    }
+
+    fn get(&mut self, key: i32) -> i32 {
+        match self.values.get(&key).cloned() {
+            Some(value) => {
+                self.touch(key);
+                value
+            }
+            None => -1,
+        }
+    }
+
+    fn put(&mut self, key: i32, value: i32) {
+        self.values.insert(key, value);
+        self.touch(key);
+        if self.values.len() > self.capacity {
+            let oldest = self.order.pop_front().unwrap();
+            self.values.remove(&oldest);
+        }
+    }
+}
+
+struct MedianFinder {
+    numbers: BinaryHeap<i32>,
+}
+
+impl MedianFinder {
+    fn new() -> Self {
+        MedianFinder {
+            numbers: BinaryHeap::new(),
+        }
+    }
+
+    fn add_num(&mut self, num: i32) {
+        self.numbers.push(num);
+    }
+
+    fn find_median(&self) -> f64 {
+        let sorted = self.numbers.clone().into_sorted_vec();
+        let n = sorted.len();
+        (sorted[(n - 1) / 2] + sorted[n / 2]) as f64 / 2.0
+    }
+}
+
+#[test]
+fn test1() {
+    let operations = r#"["LRUCache","put","put","get","put","get","put","get","get","get"]"#;
+    let arguments = "[[2],[1,1],[2,2],[1],[3,3],[2],[4,4],[1],[3],[4]]";
+    let run = |expected: &str| {
+        run_design(
+            operations,
+            arguments,
+            expected,
+            design_dispatch!(LRUCache::new(capacity), get(key), put(key, value)),
+        )
+    };
+    assert_eq!(Ok(()), run("[null,null,null,1,null,-1,null,-1,3,4]"));
+    assert_eq!(
+        Err("step 5 `get(2)`: expected 2, got -1".to_string()),
+        run("[null,null,null,1,null,2,null,-1,3,4]")
+    );
+    assert_eq!(
+        Err("10 operations, 10 argument lists and 2 expected outputs".to_string()),
+        run("[null,null]")
+    );
+}
+
+#[test]
+fn test2() {
+    let dispatch = || design_dispatch!(MedianFinder::new(), add_num(num), find_median());
+    assert_eq!(
+        Ok(()),
+        run_design(
+            r#"["MedianFinder","addNum","addNum","findMedian","addNum","findMedian"]"#,
+            "[[],[1],[2],[],[3],[]]",
+            "[null,null,null,1.50000,null,2]",
+            dispatch(),
+        )
+    );
+    assert_eq!(
+        Err("step 1 `addNum(1,2)`: `addNum` takes 1 arguments, found 2".to_string()),
+        run_design(
+            r#"["MedianFinder","addNum"]"#,
+            "[[],[1,2]]",
+            "[null,null]",
+            dispatch()
+        )
+    );
+    assert_eq!(
+        Err(
+            "step 1 `addNum(\"a\")`: argument 1: expected an integer, found a string `\"a\"`"
+                .to_string()
+        ),
+        run_design(
+            r#"["MedianFinder","addNum"]"#,
+            r#"[[],["a"]]"#,
+            "[null,null]",
+            dispatch()
+        )
+    );
+    assert_eq!(
+        Err("step 1 `median()`: unknown operation `median`".to_string()),
+        run_design(
+            r#"["MedianFinder","median"]"#,
+            "[[],[]]",
+            "[null,1]",
+            dispatch()
+        )
+    );
+    assert_eq!(
+        Err("step 0 `addNum(1)`: no object was constructed".to_string()),
+        run_design(r#"["addNum"]"#, "[[1]]", "[null]", dispatch())
+    );
+}
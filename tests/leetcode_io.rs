@@ -0,0 +1,66 @@
+extern crate cauly_rust_leetcode_utils;
+use cauly_rust_leetcode_utils::leetcode_io::*;
+
+#[test]
+fn test1() {
+    assert_eq!(Ok(vec![1, 2, 3]), parse::<Vec<i32>>("[1,2,3]"));
+    assert_eq!(
+        Ok(vec![vec![1, 2], vec![3]]),
+        parse::<Vec<Vec<i32>>>("[[1,2],[3]]")
+    );
+    assert_eq!(
+        Ok(vec![vec!["a".to_string(), "b".to_string()]]),
+        parse::<Vec<Vec<String>>>("[[\"a\",\"b\"]]")
+    );
+    assert_eq!(
+        Ok(vec![vec!['1', '0'], vec!['0', '1']]),
+        parse::<Vec<Vec<char>>>("[[\"1\",\"0\"],[\"0\",\"1\"]]")
+    );
+    assert_eq!(
+        Ok("a \"b\"\n".to_string()),
+        parse::<String>(r#""a \"b\"\n""#)
+    );
+    assert_eq!(Ok(true), parse::<bool>("true"));
+    assert_eq!(
+        Ok(vec![Some(1), None, Some(-3)]),
+        parse::<Vec<Option<i64>>>("[1, null, -3]")
+    );
+    assert_eq!(Ok(2.5), parse::<f64>("2.50000"));
+    assert_eq!(Ok(2.0), parse::<f64>("2"));
+    assert_eq!(Ok(Vec::<usize>::new()), parse::<Vec<usize>>(" [ ] "));
+    assert_eq!(Ok(()), parse::<()>("null"));
+}
+
+#[test]
+fn test2() {
+    assert!(parse::<Vec<i32>>("[1,2").is_err());
+    assert!(parse::<Vec<i32>>("[1,\"a\"]").is_err());
+    assert!(parse::<i32>("3000000000").is_err());
+    assert!(parse::<usize>("-1").is_err());
+    assert!(parse::<char>("\"ab\"").is_err());
+    assert!(parse::<i32>("1 2").is_err());
+    assert!(parse::<bool>("null").is_err());
+}
+
+#[test]
+fn test3() {
+    assert_eq!("[1,2,3]", format(&vec![1, 2, 3]));
+    assert_eq!("[[\"1\",\"0\"]]", format(&vec![vec!['1', '0']]));
+    assert_eq!("[1,null]", format(&vec![Some(1), None]));
+    assert_eq!("2.50000", format(&2.5));
+    assert_eq!("\"a\\\"b\"", format(&"a\"b".to_string()));
+    assert_eq!("null", format(&()));
+    let value = parse_value("[[1,2.5],[\"x\",true,null]]").unwrap();
+    assert_eq!(
+        Value::List(vec![
+            Value::List(vec![Value::Int(1), Value::Float(2.5)]),
+            Value::List(vec![
+                Value::Str("x".to_string()),
+                Value::Bool(true),
+                Value::Null
+            ]),
+        ]),
+        value
+    );
+    assert_eq!("[[1,2.50000],[\"x\",true,null]]", value.to_string());
+}
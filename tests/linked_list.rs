@@ -0,0 +1,66 @@
+extern crate cauly_rust_leetcode_utils;
+use cauly_rust_leetcode_utils::leetcode_io::*;
+use cauly_rust_leetcode_utils::linked_list::*;
+
+#[test]
+fn test1() {
+    let list = ListNode::from_vec(&[1, 2, 3]);
+    assert_eq!(vec![1, 2, 3], list.to_vec());
+    assert_eq!(3, list.len());
+    assert_eq!("Some(1 -> 2 -> 3)", format!("{:?}", list));
+    assert_eq!("[1,2,3]", list.to_leetcode_string());
+    assert_eq!(Ok(list.clone()), ListNode::from_string("[1, 2, 3]"));
+    assert_eq!(Ok(None), ListNode::from_string("[]"));
+    assert!(ListNode::from_string("[1,").is_err());
+    assert_eq!(
+        Ok(vec![list.clone(), None]),
+        parse::<Vec<Option<Box<ListNode>>>>("[[1,2,3],[]]")
+    );
+    assert_eq!(None::<Box<ListNode>>.len(), 0);
+    assert_eq!(Some(Box::new(ListNode::new(7))), ListNode::from_vec(&[7]));
+}
+
+#[test]
+fn test2() {
+    let list = ListNode::from_vec(&[1, 2, 3, 4, 5]);
+    assert_eq!(
+        vec![2, 4],
+        list.values().filter(|v| v % 2 == 0).collect::<Vec<_>>()
+    );
+    assert_eq!(5, list.nodes().last().unwrap().val);
+    assert_eq!(Some(3), list.middle().map(|n| n.val));
+    assert_eq!(
+        Some(4),
+        ListNode::from_vec(&[1, 2, 3, 4, 5, 6])
+            .middle()
+            .map(|n| n.val)
+    );
+    assert_eq!(None, None.middle());
+    assert_eq!(vec![5, 4, 3, 2, 1], list.clone().reverse().to_vec());
+
+    let (a, b) = list.clone().split_at(2);
+    assert_eq!((vec![1, 2], vec![3, 4, 5]), (a.to_vec(), b.to_vec()));
+    let (a, b) = list.clone().split_at(0);
+    assert_eq!((None, list.clone()), (a, b));
+    let (a, b) = list.clone().split_at(9);
+    assert_eq!((list.clone(), None), (a, b));
+}
+
+#[test]
+fn test3() {
+    let a = ListNode::from_vec(&[1, 3, 5, 5]);
+    let b = ListNode::from_vec(&[2, 3, 6]);
+    assert_eq!(
+        vec![1, 2, 3, 3, 5, 5, 6],
+        a.clone().merge_sorted(b).to_vec()
+    );
+    assert_eq!(a.clone(), a.clone().merge_sorted(None));
+    assert_eq!(a.clone(), None.merge_sorted(a.clone()));
+
+    let mut list = ListNode::from_vec(&[1, 2]);
+    list.append(ListNode::from_vec(&[3]));
+    assert_eq!(vec![1, 2, 3], list.to_vec());
+    let mut empty = None;
+    empty.append(list.clone());
+    assert_eq!(list, empty);
+}
@@ -0,0 +1,69 @@
+extern crate cauly_rust_leetcode_utils;
+use cauly_rust_leetcode_utils::rlfmt::*;
+use cauly_rust_leetcode_utils::*;
+
+#[test]
+fn test1() {
+    let names = module_names();
+    for module in MODULES {
+        assert_eq!(
+            module.deps.to_vec(),
+            module_dependencies(module.source, &names)
+                .into_iter()
+                .filter(|&m| m != module.name)
+                .collect::<Vec<_>>(),
+            "deps of {}",
+            module.name
+        );
+
+        let mut items = Vec::new();
+        for item in parse_items(module.source, (0, module.source.len())) {
+            let name = match item.name {
+                Some(name) => name,
+                None => continue,
+            };
+            if item.kind == ItemKind::MacroRules {
+                if item.attrs.iter().any(|a| a == "#[macro_export]") {
+                    items.push(format!("{}!", name));
+                }
+                continue;
+            }
+            let text = &module.source[item.start..item.end];
+            let after_attrs = match item.attrs.last() {
+                Some(attr) => &text[text.find(attr.as_str()).unwrap() + attr.len()..],
+                None => text,
+            };
+            let is_pub = after_attrs
+                .lines()
+                .map(|l| l.trim())
+                .find(|l| !l.is_empty() && !l.starts_with("//"))
+                .is_some_and(|l| l.starts_with("pub "));
+            if is_pub {
+                items.push(name);
+            }
+        }
+        assert_eq!(module.items.to_vec(), items, "items of {}", module.name);
+    }
+}
+
+#[test]
+fn test2() {
+    assert_eq!(MODULES.len(), module_names().len());
+    assert_eq!(Ok(MODULES[0].source), get_module_source(MODULES[0].name));
+    assert!(get_module_source("define_dp").is_err());
+    assert_eq!(
+        Some("binary_tree"),
+        which_module("TraversalType").map(|m| m.name)
+    );
+    assert!(which_module("Solution").is_none());
+
+    let args = parse_args(["--which", "DPIndexer", "--list"]).unwrap();
+    assert_eq!(Some("DPIndexer".to_string()), args.which);
+    assert!(args.list);
+    assert_eq!(
+        Ok("use cauly_rust_leetcode_utils::dp::DPIndexer;".to_string()),
+        which("DPIndexer")
+    );
+    assert_eq!(MODULES.len(), module_table().lines().count());
+    assert!(module_table().contains("dp             DP, DPIndexer, define_dp!\n"));
+}
@@ -0,0 +1,79 @@
+extern crate cauly_rust_leetcode_utils;
+use cauly_rust_leetcode_utils::binary_tree::*;
+use cauly_rust_leetcode_utils::random::*;
+
+#[test]
+fn test1() {
+    let numbers = |seed| {
+        let mut rng = Rng::new(seed);
+        (0..20).map(|_| rng.range(-3, 3)).collect::<Vec<_>>()
+    };
+    assert_eq!(numbers(7), numbers(7));
+    assert_ne!(numbers(7), numbers(8));
+    assert!(numbers(0).iter().all(|v| (-3..=3).contains(v)));
+
+    let mut rng = Rng::new(0);
+    let extremes: Vec<i32> = (0..100).map(|_| rng.range(i32::MIN, i32::MAX)).collect();
+    assert!(extremes.iter().any(|&v| v < 0) && extremes.iter().any(|&v| v > 0));
+    assert_eq!(
+        vec![5; 3],
+        (0..3).map(|_| rng.range(5, 5)).collect::<Vec<_>>()
+    );
+
+    let mut values = rng.distinct(10, 1, 10);
+    values.sort_unstable();
+    assert_eq!((1..=10).collect::<Vec<_>>(), values);
+    let mut values = rng.distinct(1000, i32::MIN, i32::MAX);
+    values.sort_unstable();
+    values.dedup();
+    assert_eq!(1000, values.len());
+}
+
+#[test]
+fn test2() {
+    let mut rng = Rng::new(2024);
+    for n in 0..50 {
+        let (tree, s) = rng.tree(n, 0, 9);
+        assert_eq!(n, tree.node_count());
+        assert_eq!(Ok(()), TreeNode::from_string(&s).unwrap().compare(&tree));
+        assert!(tree
+            .traverse(TraversalType::Preorder)
+            .all(|v| (0..=9).contains(&v.node.borrow().val)));
+
+        let (bst, s) = rng.bst(n, -100, 100);
+        assert_eq!(n, bst.node_count());
+        assert!(bst.is_valid_bst());
+        assert_eq!(s, bst.to_leetcode_string());
+    }
+    assert_eq!(Rng::new(5).tree(30, 0, 1), Rng::new(5).tree(30, 0, 1));
+    assert_eq!((None, "[]".to_string()), rng.bst(0, 1, 0));
+}
+
+#[test]
+fn test3() {
+    let mut rng = Rng::new(1);
+    let (left, s) = rng.skewed_tree(5, 1, 1, Skew::Left);
+    assert_eq!("[1,1,null,1,null,1,null,1]", s);
+    assert_eq!(vec![1; 5], left.left_view());
+    let (right, s) = rng.skewed_tree(4, 2, 2, Skew::Right);
+    assert_eq!("[2,null,2,null,2,null,2]", s);
+    assert_eq!(4, right.height());
+    let (zigzag, _) = rng.skewed_tree(100, -5, 5, Skew::Random);
+    assert_eq!((100, 100), (zigzag.height(), zigzag.node_count()));
+
+    let (complete, s) = rng.complete_tree(6, 0, 0);
+    assert_eq!("[0,0,0,0,0,0]", s);
+    assert_eq!(3, complete.height());
+    for n in 0..40 {
+        let (tree, s) = rng.complete_tree(n, 0, 9);
+        assert_eq!(n, tree.node_count());
+        assert!(!s.contains("null"));
+    }
+    let (perfect, s) = rng.perfect_tree(4, 3, 3);
+    assert_eq!(format!("[{}]", vec!["3"; 15].join(",")), s);
+    assert_eq!(
+        vec![1, 2, 4, 8],
+        perfect.levels().map(|l| l.len()).collect::<Vec<_>>()
+    );
+    assert_eq!((None, "[]".to_string()), rng.perfect_tree(0, 0, 0));
+}
@@ -0,0 +1,693 @@
+extern crate cauly_rust_leetcode_utils;
+use cauly_rust_leetcode_utils::get_module_source;
+use cauly_rust_leetcode_utils::rlfmt::*;
+
+#[test]
+fn test1() {
+    let lookup = |name: &str| match name {
+        "grid_bfs" => Some("use crate::union_find::UnionFind4Usize;\nuse crate::grid::*;\n"),
+        "grid" => Some("use crate::union_find::UnionFind4Usize;\npub struct Grid;\n"),
+        "union_find" => Some("pub struct UnionFind4Usize;\n"),
+        _ => None,
+    };
+    let modules = ["grid", "grid_bfs", "union_find"];
+    assert_eq!(
+        Ok(vec!["union_find", "grid", "grid_bfs"]),
+        resolve_dependencies(&["grid_bfs"], &modules, lookup)
+    );
+    assert_eq!(
+        Ok(vec!["union_find", "grid"]),
+        resolve_dependencies(&["union_find", "grid"], &modules, lookup)
+    );
+}
+
+#[test]
+fn test2() {
+    let lookup = |name: &str| match name {
+        "a" => Some("use crate::b::B;\n"),
+        "b" => Some("fn f() { crate::a::A; }\n"),
+        _ => None,
+    };
+    assert!(resolve_dependencies(&["a"], &["a", "b"], lookup).is_err());
+    assert!(resolve_dependencies(&["c"], &["a", "b"], lookup).is_err());
+}
+
+#[test]
+fn test3() {
+    let content = "use cauly_rust_leetcode_utils::union_find::*;
+use cauly_rust_leetcode_utils::union_find::UnionFind;
+use std::collections::HashMap;
+pub struct Solution;
+";
+    let result = flatten(
+        content,
+        &Options {
+            prune: false,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(1, result.matches("pub struct UnionFind4Usize").count());
+    assert_eq!(1, result.matches(HEADER).count());
+    assert_eq!(1, result.matches("use std::collections::HashMap;").count());
+    assert!(!result.contains("pub struct Solution;"));
+}
+
+#[test]
+fn test4() {
+    let content = "extern crate cauly_rust_leetcode_utils;
+use cauly_rust_leetcode_utils::{
+    dp::*,
+    union_find::{UnionFind as UF, UnionFind4Usize},
+};
+use cauly_rust_leetcode_utils::define_dp;
+use std::rc::Rc;
+";
+    let result = flatten(
+        content,
+        &Options {
+            prune: false,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert!(!result.contains("cauly_rust_leetcode_utils::"));
+    assert!(!result.contains("extern crate"));
+    assert_eq!(1, result.matches("macro_rules! define_dp").count());
+    assert_eq!(1, result.matches("pub struct UnionFind4Usize").count());
+    assert!(result.contains("use self::UnionFind as UF;"));
+    assert!(result.contains("use std::rc::Rc;"));
+}
+
+#[test]
+fn test5() {
+    let content = "use cauly_rust_leetcode_utils::grid as g;
+use cauly_rust_leetcode_utils::binary_tree::TraversalType::Inorder;
+fn f() {
+    use cauly_rust_leetcode_utils::grid::Grid;
+}
+";
+    let result = flatten(content, &Options::default()).unwrap();
+    assert!(result.starts_with(HEADER));
+    assert!(result.contains("mod g { pub use super::*; }"));
+    assert!(result.contains("use self::TraversalType::Inorder;"));
+    assert!(result.ends_with("fn f() {\n    use crate::Grid;\n}\n"));
+    assert!(flatten(
+        "use cauly_rust_leetcode_utils::nothing::*;",
+        &Options::default()
+    )
+    .is_err());
+}
+
+#[test]
+fn test6() {
+    let content = "use cauly_rust_leetcode_utils::graph::*;
+use cauly_rust_leetcode_utils::union_find::UnionFind;
+use cauly_rust_leetcode_utils::{define_dp, dp::*};
+pub struct Solution;
+
+struct Dp {
+    i: usize,
+}
+define_dp! {Dp, i: 10}
+
+impl Solution {
+    pub fn f(edges: Vec<Vec<i32>>) -> Option<i32> {
+        let mut uf = UnionFind::new();
+        uf.add(1);
+        let dp = DP::new::<Dp>(0);
+        Graph::new(&edges, true, true, |x, _| x).dijkstra(0, dp.get(&Dp { i: 1 }))
+    }
+}
+";
+    let result = flatten(content, &Options::default()).unwrap();
+    assert!(result.contains("pub fn dijkstra("));
+    assert!(result.contains("pub fn get_connects("));
+    assert!(!result.contains("pub fn dijkstra_all("));
+    assert!(!result.contains("pub fn bfs_rev<"));
+    assert!(result.contains("pub struct UnionFind4Usize"));
+    assert!(result.contains("    pub fn add(&mut self) -> usize {"));
+    assert!(!result.contains("pub fn is_connected("));
+    assert!(!result.contains("macro_rules! define_dp"));
+    assert!(result
+        .contains("impl DPIndexer for Dp {\n    fn index(&self) -> usize {\n        self.i\n"));
+    assert!(result.contains("fn sizes() -> Vec<usize>;"));
+    assert!(!result.contains("pub fn get_mut<"));
+    assert!(result.contains("// #region Graph"));
+    assert!(result.contains("// #endregion"));
+}
+
+#[test]
+fn test7() {
+    let content = "use cauly_rust_leetcode_utils::binary_tree::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+pub struct Solution;
+
+impl Solution {
+    pub fn f(root: Option<Rc<RefCell<TreeNode>>>) -> i32 {
+        root.get_val().unwrap_or(0)
+    }
+}
+";
+    let flattened = flatten_mapped(content, &Options::default()).unwrap();
+    assert_eq!(Some(&Origin::Generated), flattened.origin(1));
+    let line = flattened
+        .code
+        .lines()
+        .position(|l| l.contains("get_val().unwrap_or"))
+        .unwrap();
+    assert_eq!(Some(&Origin::Solution(8)), flattened.origin(line + 1));
+    let line = flattened
+        .code
+        .lines()
+        .position(|l| l.starts_with("pub struct TreeNode"))
+        .unwrap();
+    let source_line = get_module_source("binary_tree")
+        .unwrap()
+        .lines()
+        .position(|l| l.starts_with("pub struct TreeNode"))
+        .unwrap();
+    assert_eq!(
+        Some(&Origin::Library("binary_tree", Some(source_line + 1))),
+        flattened.origin(line + 1)
+    );
+    let diagnostics = verify(&flattened, "src/lib.rs").unwrap();
+    assert!(diagnostics.iter().all(|d| d.level != "error"));
+
+    let flattened = flatten_mapped(
+        &content.replace("get_val()", "get_value()"),
+        &Options::default(),
+    )
+    .unwrap();
+    let diagnostics = verify(&flattened, "src/lib.rs").unwrap();
+    assert_eq!(1, diagnostics.len());
+    assert_eq!("error", diagnostics[0].level);
+    assert_eq!(Some("E0599".to_string()), diagnostics[0].code);
+    assert!(diagnostics[0].location.starts_with("src/lib.rs:8:"));
+}
+
+#[test]
+fn test8() {
+    let content = "use cauly_rust_leetcode_utils::{grid::*, union_find::UnionFind4Usize};
+pub struct Solution;
+
+impl Solution {
+    // count the islands
+    pub fn f(grid: Vec<Vec<char>>) -> usize {
+        let g = Grid::from(grid);
+        let mut uf = UnionFind4Usize::new(g.width() * g.height());
+        println!(\"{:?}\", uf.count()); /* debug */
+        uf.count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cauly_rust_leetcode_utils::dp::*;
+
+    #[test]
+    fn test1() {}
+}
+";
+    let result = flatten(content, &Options::default()).unwrap();
+    assert!(!result.contains("println!"));
+    assert!(result.contains("// count the islands"));
+    assert!(!result.contains("mod tests"));
+    assert!(!result.contains("pub struct DP"));
+
+    let options = Options {
+        not_inlined: vec!["union_find"],
+        keep_prints: true,
+        keep_tests: true,
+        strip_comments: true,
+        ..Default::default()
+    };
+    let result = flatten(content, &options).unwrap();
+    assert!(result.contains("use cauly_rust_leetcode_utils::union_find::UnionFind4Usize;"));
+    assert!(result.contains("pub struct Grid"));
+    assert!(result.contains("        println!(\"{:?}\", uf.count());\n"));
+    assert!(result.contains("mod tests"));
+    assert!(!result.contains("count the islands"));
+    assert!(!result.contains("#region"));
+    assert!(result.starts_with(HEADER));
+}
+
+#[test]
+fn test9() {
+    let args = parse_args(["--keep-prints", "--strip-comments", "--output=a.rs", "b.rs"]).unwrap();
+    assert!(args.options.keep_prints && args.options.strip_comments);
+    assert!(!args.options.keep_tests);
+    assert_eq!(Some("a.rs".to_string()), args.output);
+    assert_eq!(Some("b.rs".to_string()), args.input);
+
+    let args = parse_args(["--no-inline=grid,dp", "--inline=dp"]).unwrap();
+    assert_eq!(vec!["grid"], args.options.not_inlined);
+    assert!(parse_args(["-o"]).is_err());
+    assert!(parse_args(["--keep"]).is_err());
+    assert!(parse_args(["a.rs", "b.rs"]).is_err());
+    for input in ["nonexist.rs", "src/missing/p0001.rs"] {
+        let error = parse_args([input]).unwrap().input_file().unwrap_err();
+        assert!(
+            error.starts_with(&format!("can't read `{}`: ", input)),
+            "{}",
+            error
+        );
+    }
+    assert!(parse_args(["src/*.rs"])
+        .unwrap()
+        .input_file()
+        .unwrap_err()
+        .contains("more than one file"));
+
+    let content = "use cauly_rust_leetcode_utils::heap::Heap;\n";
+    let error = flatten(content, &Options::default()).unwrap_err();
+    assert!(error.contains("unknown module `heap`"));
+}
+
+#[test]
+fn test10() {
+    let content = "pub struct Solution;
+
+impl Solution {
+    pub fn f(nums: Vec<i32>) -> i32 {
+        let sum: i32 = dbg!(nums.iter().sum());
+        dbg!(sum);
+        if sum > 0 { println!(\"positive\") } else { eprintln!(\"{}\", sum) };
+        eprintln!(
+            \"{:?}\",
+            nums
+        );
+        let s = \"println!(1);\";
+        match sum {
+            0 => print!(\"zero\"),
+            _ => {}
+        }
+        println!(\"{}\", s);
+        sum
+    }
+
+    #[cfg(test)]
+    fn helper() {}
+}
+
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test1() {
+        assert_eq!(6, Solution::f(vec![1, 2, 3]));
+    }
+}
+";
+    let result = flatten(content, &Options::default()).unwrap();
+    assert!(result.contains("        let sum: i32 = (nums.iter().sum());\n"));
+    assert!(!result.contains("dbg!"));
+    assert!(!result.contains("eprintln!"));
+    assert!(result.contains("if sum > 0 { () } else { () };"));
+    assert!(result.contains("0 => (),"));
+    assert!(result.contains("let s = \"println!(1);\";"));
+    assert!(result.contains("//        println!(\"{}\", s);\n"));
+    assert!(!result.contains("fn helper"));
+    assert!(!result.contains("fn test1"));
+    assert!(result.contains("mod tests {\n    use super::*;\n\n}"));
+
+    let options = Options {
+        keep_prints: true,
+        keep_tests: true,
+        ..Default::default()
+    };
+    let result = flatten(content, &options).unwrap();
+    assert_eq!(content.replace("pub struct Solution;\n", ""), result);
+}
+
+#[test]
+fn test11() {
+    let root = std::env::temp_dir().join(format!("rlfmt-test11-{}", std::process::id()));
+    let write = |path: &str, content: &str| {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    };
+    write(
+        "src/p0001_two_sum.rs",
+        "mod helper;
+pub struct Solution;
+
+impl Solution {
+    pub fn two_sum(nums: Vec<i32>, target: i32) -> Vec<i32> {
+        helper::find(&nums, target)
+    }
+}
+",
+    );
+    write(
+        "src/p0001_two_sum/helper.rs",
+        "mod inner;
+use cauly_rust_leetcode_utils::union_find::UnionFind4Usize;
+
+pub fn find(nums: &[i32], target: i32) -> Vec<i32> {
+    let _ = UnionFind4Usize::new(nums.len());
+    inner::find(nums, target)
+}
+",
+    );
+    write(
+        "src/p0001_two_sum/helper/inner.rs",
+        "pub fn find(nums: &[i32], target: i32) -> Vec<i32> {
+    let _ = (nums, target);
+    vec![0, 1]
+}
+",
+    );
+    write("src/bin/p0002_add_two_numbers.rs", "fn main() {}\n");
+    write("src/lib.rs", "mod p0001_two_sum;\n");
+
+    let expected = root.join("src/p0001_two_sum.rs");
+    assert_eq!(Ok(expected.clone()), find_problem(&root, "1"));
+    assert_eq!(Ok(expected.clone()), find_problem(&root, "p0001"));
+    assert_eq!(Ok(expected.clone()), find_problem(&root, "*two_sum*"));
+    assert!(find_problem(&root, "2")
+        .unwrap()
+        .ends_with("src/bin/p0002_add_two_numbers.rs"));
+    assert!(find_problem(&root, "src/*.rs").is_err());
+    assert!(find_problem(&root, "3").is_err());
+
+    let flattened = flatten_file(&expected, &Options::default()).unwrap();
+    assert!(flattened
+        .code
+        .contains("mod helper {\nmod inner {\npub fn find("));
+    assert!(flattened.code.contains("pub struct UnionFind4Usize"));
+    let line = flattened
+        .code
+        .lines()
+        .position(|l| l.contains("inner::find(nums, target)"))
+        .unwrap();
+    let helper = root.join("src/p0001_two_sum/helper.rs");
+    assert_eq!(
+        Some(&Origin::File(helper.display().to_string(), 6)),
+        flattened.origin(line + 1)
+    );
+    let line = flattened
+        .code
+        .lines()
+        .position(|l| l.contains("helper::find(&nums, target)"))
+        .unwrap();
+    assert_eq!(Some(&Origin::Solution(6)), flattened.origin(line + 1));
+    let diagnostics = verify(&flattened, "src/p0001_two_sum.rs").unwrap();
+    assert!(diagnostics.iter().all(|d| d.level != "error"));
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test12() {
+    let root = std::env::temp_dir().join(format!("rlfmt-test12-{}", std::process::id()));
+    std::fs::create_dir_all(root.join("src/p0001")).unwrap();
+    let solution = root.join("src/p0001.rs");
+    let helper = root.join("src/p0001/helper.rs");
+    std::fs::write(
+        &solution,
+        "mod helper;\nuse cauly_rust_leetcode_utils::union_find::UnionFind4Usize;\nfn f() { UnionFind4Usize::new(1); }\n",
+    )
+    .unwrap();
+    std::fs::write(&helper, "pub fn g() {}\n").unwrap();
+
+    let flattened = flatten_file(&solution, &Options::default()).unwrap();
+    let files = watched_files(&solution, &flattened);
+    assert_eq!(
+        vec![solution.clone(), helper.clone(), library_file("union_find")],
+        files
+    );
+    assert!(summary(&flattened).contains("inlined from union_find"));
+
+    let mut watcher = Watcher::new(files);
+    assert!(watcher.changed().is_empty());
+    std::fs::write(&helper, "pub fn g() { let _ = 1; }\n").unwrap();
+    assert_eq!(vec![helper.clone()], watcher.changed());
+    assert!(watcher.changed().is_empty());
+    std::fs::remove_file(&helper).unwrap();
+    assert_eq!(vec![helper.clone()], watcher.changed());
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test13() {
+    let content = "use cauly_rust_leetcode_utils::{define_dp, dp::*};
+pub struct Solution;
+
+const N: usize = 3;
+struct Dp {
+    i: usize,
+    j: usize,
+    k: usize,
+    l: usize,
+}
+define_dp!(Dp, i: N + 1, j: 2, k: N.pow(2), l: N as usize);
+
+impl Solution {
+    pub fn f() -> i32 {
+        let dp = DP::new::<Dp>(1);
+        dp.get(&Dp { i: 3, j: 1, k: 8, l: 2 })
+    }
+}
+";
+    let flattened = flatten_mapped(content, &Options::default()).unwrap();
+    assert!(!flattened.code.contains("define_dp"));
+    assert!(flattened.code.contains(
+        "        self.i * 2 * N.pow(2) * (N as usize)
+            + self.j * N.pow(2) * (N as usize)
+            + self.k * (N as usize)
+            + self.l
+"
+    ));
+    assert!(flattened
+        .code
+        .contains("vec![N + 1, 2, N.pow(2), N as usize]"));
+    let line = flattened
+        .code
+        .lines()
+        .position(|l| l.contains("fn sizes() -> Vec<usize> {"))
+        .unwrap();
+    assert_eq!(Some(&Origin::Solution(11)), flattened.origin(line + 1));
+    let diagnostics = verify(&flattened, "src/lib.rs").unwrap();
+    assert!(diagnostics.iter().all(|d| d.level != "error"));
+
+    let error = flatten("define_dp! {Dp, i}", &Options::default()).unwrap_err();
+    assert!(error.contains("expected `field: length`"));
+}
+
+#[test]
+fn test14() {
+    let text = "Example 1:
+Input: numCourses = 2, prerequisites = [[1,0]]
+Output: true
+Explanation: There are a total of 2 courses to take.
+
+Example 2:
+Input: nums = [1,2,3], k = 1
+Output: [3,1,2]
+
+Example 3:
+Input: root = [], words = [\"a\",\"b c\"]
+Output: null
+";
+    let source = "impl Solution {
+    pub fn can_finish(num_courses: i32, prerequisites: Vec<Vec<i32>>) -> bool {
+        true
+    }
+    pub fn rotate(nums: &mut Vec<i32>, k: i32) {}
+    pub fn f(root: Option<Rc<RefCell<TreeNode>>>, words: Vec<String>) -> Option<i32> {
+        None
+    }
+}
+";
+    let examples = parse_examples(text).unwrap();
+    assert_eq!(3, examples.len());
+    let tests = scaffold(&examples, &solution_signatures(source)).unwrap();
+    assert!(tests.starts_with("#[cfg(test)]\nmod tests {\n    use super::*;\n"));
+    assert!(tests.contains(
+        "    #[test]
+    fn test1() {
+        let num_courses = 2;
+        let prerequisites = vec![vec![1, 0]];
+        assert_eq!(true, Solution::can_finish(num_courses, prerequisites));
+    }
+"
+    ));
+    assert!(tests.contains(
+        "        Solution::rotate(&mut nums, k);
+        assert_eq!(vec![3, 1, 2], nums);
+"
+    ));
+    assert!(tests.contains("        let root = None;\n"));
+    assert!(tests.contains("        let words = vec![\"a\".to_string(), \"b c\".to_string()];\n"));
+    assert!(tests.contains("        assert_eq!(None, Solution::f(root, words));\n"));
+
+    // float outputs are rounded by LeetCode
+    let examples = parse_examples("Input: x = 2.10000, n = 3\nOutput: 9.26100").unwrap();
+    let source = "impl Solution {
+    pub fn my_pow(x: f64, n: i32) -> f64 {
+        x.powi(n)
+    }
+}
+";
+    let tests = scaffold(&examples, &solution_signatures(source)).unwrap();
+    assert!(tests.contains("        let x = 2.1;\n"));
+    assert!(tests.contains("        assert!((9.261 - Solution::my_pow(x, n)).abs() < 1e-5);\n"));
+    assert!((9.261 - 2.1f64.powi(3)).abs() < 1e-5 && 9.261 != 2.1f64.powi(3));
+
+    let error = scaffold(&parse_examples("Input: x = 1\nOutput: 1").unwrap(), &[]).unwrap_err();
+    assert!(error.contains("no method"));
+    assert!(parse_examples("nothing here").is_err());
+    assert!(parse_args(["scaffold"]).is_err());
+    let args = parse_args(["scaffold", "examples.txt", "1"]).unwrap();
+    assert_eq!(Some("examples.txt".to_string()), args.scaffold);
+    assert_eq!(Some("1".to_string()), args.input);
+}
+
+#[test]
+fn test15() {
+    let content = "use cauly_rust_leetcode_utils::binary_search::*;
+use cauly_rust_leetcode_utils::graph::Graph;
+pub struct Solution;
+
+/// Solution of the problem.
+impl Solution {
+    // the edges are directed
+
+    pub fn f(edges: Vec<Vec<i32>>) -> usize {
+        let graph = Graph::new(&edges, true, true, |x, _| x); /* weighted */
+        let search = BinarySearch::from(vec![1, 2, 3], |x| *x);
+        search.how_many_values_smaller_than(&2) + graph.get_connects(0).count()
+    }
+}
+";
+    let plain = flatten_mapped(content, &Options::default()).unwrap();
+    let options = Options {
+        minify: true,
+        ..Default::default()
+    };
+    let minified = flatten_mapped(content, &options).unwrap();
+    assert!(minified.code.len() < plain.code.len());
+    assert!(plain.code.contains("/// "));
+
+    let lines = |f: &Flattened, library: bool| -> Vec<String> {
+        f.code
+            .lines()
+            .zip(&f.origins)
+            .filter(|(_, o)| matches!(o, Origin::Library(..)) == library)
+            .map(|(l, _)| l.to_string())
+            .collect()
+    };
+    let library = lines(&minified, true);
+    assert!(!library.is_empty());
+    assert!(library
+        .iter()
+        .all(|l| !l.trim().is_empty() && !l.contains("//")));
+    assert_eq!(lines(&plain, false), lines(&minified, false));
+    assert!(minified.code.starts_with(HEADER));
+    assert!(minified.code.contains("\n/// Solution of the problem.\n"));
+    assert!(minified.code.contains("    // the edges are directed\n\n"));
+
+    let diagnostics = verify(&minified, "src/lib.rs").unwrap();
+    assert!(diagnostics.iter().all(|d| d.level != "error"));
+}
+
+#[test]
+fn test16() {
+    let content = "use cauly_rust_leetcode_utils::linked_list::*;
+pub struct Solution;
+
+impl Solution {
+    pub fn reverse_list(head: Option<Box<ListNode>>) -> Option<Box<ListNode>> {
+        head.reverse()
+    }
+}
+";
+    let flattened = flatten_mapped(content, &Options::default()).unwrap();
+    assert!(flattened.code.contains("pub struct ListNode {"));
+    assert!(flattened.code.contains("fn reverse(self) -> Self"));
+    assert!(!flattened.code.contains("fn merge_sorted"));
+    let (source, _) = verification_source(&flattened);
+    assert_eq!(1, source.matches("pub struct ListNode").count());
+    let diagnostics = verify(&flattened, "src/lib.rs").unwrap();
+    assert!(
+        diagnostics.iter().all(|d| d.level != "error"),
+        "{:?}",
+        diagnostics
+    );
+}
+
+#[test]
+fn test17() {
+    let solution = |imports: &str| {
+        format!(
+            "use cauly_rust_leetcode_utils::binary_tree::*;
+{}
+pub struct Solution;
+
+impl Solution {{
+    pub fn level_count(root: Option<Rc<RefCell<TreeNode>>>) -> i32 {{
+        let mut counts = HashMap::new();
+        let mut queue = VecDeque::new();
+        queue.push_back(root.levels().count());
+        for level in root.levels() {{
+            *counts.entry(level.len()).or_insert(0) += 1;
+        }}
+        (counts.len() + queue.len()) as i32
+    }}
+}}
+",
+            imports
+        )
+    };
+    for imports in &[
+        "use std::cell::RefCell;\nuse std::collections::{HashMap, VecDeque};\nuse std::rc::Rc;",
+        "use std::cell::RefCell;
+use std::collections::{
+    BTreeMap, BinaryHeap, HashMap, HashSet, VecDeque,
+};
+use std::rc::Rc;",
+    ] {
+        let flattened = flatten_mapped(&solution(imports), &Options::default()).unwrap();
+        assert!(!flattened.code.contains("use std::collections::VecDeque;"));
+        assert_eq!(
+            imports.matches("BTreeMap").count(),
+            flattened.code.matches("BTreeMap").count()
+        );
+        let diagnostics = verify(&flattened, "src/lib.rs").unwrap();
+        assert!(
+            diagnostics.iter().all(|d| d.level != "error"),
+            "{:?}",
+            diagnostics
+        );
+    }
+}
+
+#[test]
+fn test18() {
+    // a print on the last line of a source without a final newline
+    for (source, expected) in [
+        (
+            "let n = 1;\n    println!(\"{}\", n);",
+            "let n = 1;\n//    println!(\"{}\", n);",
+        ),
+        ("print!(\"x\"); ", "//print!(\"x\"); "),
+        ("let n = 1; println!(\"{}\", n);", "let n = 1; "),
+    ] {
+        let edits = strip_edits(source, false, false);
+        assert_eq!(expected, apply_edits(source, &edits));
+    }
+}
+
+#[test]
+fn test19() {
+    // lengths are only put in parentheses where they are multiplied
+    let source = "define_dp! {Dp, i: n + 1, j: m[0] - 1, k: 2}";
+    let expanded = apply_edits(source, &expand_define_dp(source).unwrap());
+    assert!(expanded.contains("self.i * (m[0] - 1) * 2 + self.j * 2 + self.k"));
+    assert!(expanded.contains("vec![n + 1, m[0] - 1, 2]"));
+}